@@ -0,0 +1,121 @@
+/*
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+'use strict';
+
+// Types needed for TypeScript generation.
+/* eslint-disable no-unused-vars */
+/* istanbul ignore next */
+if (global === undefined) {
+    const { ClassDeclaration, EnumDeclaration, ModelFile } = require('@accordproject/concerto-core');
+}
+/* eslint-enable no-unused-vars */
+
+/**
+ * Plug-in class for Rust code-generation. This lists the functions that can be passed to the RustVisitor
+ * to extend the generated modules. Each function writes its Rust code with parameters.fileWriter.
+ */
+class AbstractRustPlugin {
+    /**
+     * Additional use declarations to generate at the top of the module of a namespace
+     * @param {ModelFile} modelFile - the model file being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addFileImports(modelFile, parameters) {
+        throw new Error('addFileImports not implemented in default plugin');
+    }
+
+    /**
+     * Additional modules to generate at the end of the module of a namespace
+     * @param {ModelFile} modelFile - the model file being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addFileModules(modelFile, parameters) {
+        throw new Error('addFileModules not implemented in default plugin');
+    }
+
+    /**
+     * Additional use declarations to generate in the module of a class
+     * @param {ClassDeclaration} clazz - the clazz being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addClassImports(clazz, parameters) {
+        throw new Error('addClassImports not implemented in default plugin');
+    }
+
+    /**
+     * Additional attributes to generate on the struct of a class
+     * @param {ClassDeclaration} clazz - the clazz being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addClassAttributes(clazz, parameters) {
+        throw new Error('addClassAttributes not implemented in default plugin');
+    }
+
+    /**
+     * Additional impl blocks to generate for the struct of a class
+     * @param {ClassDeclaration} clazz - the clazz being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addClassImpls(clazz, parameters) {
+        throw new Error('addClassImpls not implemented in default plugin');
+    }
+
+    /**
+     * Additional modules to generate after the struct of a class
+     * @param {ClassDeclaration} clazz - the clazz being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addClassModules(clazz, parameters) {
+        throw new Error('addClassModules not implemented in default plugin');
+    }
+
+    /**
+     * Additional use declarations to generate in the module of an enum
+     * @param {EnumDeclaration} enumDecl - the enum being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addEnumImports(enumDecl, parameters) {
+        throw new Error('addEnumImports not implemented in default plugin');
+    }
+
+    /**
+     * Additional attributes to generate on an enum
+     * @param {EnumDeclaration} enumDecl - the enum being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addEnumAttributes(enumDecl, parameters) {
+        throw new Error('addEnumAttributes not implemented in default plugin');
+    }
+
+    /**
+     * Additional impl blocks to generate for an enum
+     * @param {EnumDeclaration} enumDecl - the enum being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addEnumImpls(enumDecl, parameters) {
+        throw new Error('addEnumImpls not implemented in default plugin');
+    }
+
+    /**
+     * Additional modules to generate after an enum
+     * @param {EnumDeclaration} enumDecl - the enum being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addEnumModules(enumDecl, parameters) {
+        throw new Error('addEnumModules not implemented in default plugin');
+    }
+}
+
+module.exports = AbstractRustPlugin;
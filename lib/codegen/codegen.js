@@ -15,6 +15,7 @@
 'use strict';
 
 const AbstractPlugin = require('./abstractplugin');
+const AbstractRustPlugin = require('./abstractrustplugin');
 
 const GoLangVisitor = require('./fromcto/golang/golangvisitor');
 const JSONSchemaVisitor = require('./fromcto/jsonschema/jsonschemavisitor');
@@ -30,12 +31,14 @@ const MarkdownVisitor = require('./fromcto/markdown/markdownvisitor');
 const ProtobufVisitor = require('./fromcto/protobuf/protobufvisitor');
 const OpenApiVisitor = require('./fromcto/openapi/openapivisitor');
 const AvroVisitor = require('./fromcto/avro/avrovisitor');
+const RustVisitor = require('./fromcto/rust/rustvisitor');
 
 
 const InferFromJsonSchema = require('./fromJsonSchema/cto/inferModel');
 
 module.exports = {
     AbstractPlugin,
+    AbstractRustPlugin,
     GoLangVisitor,
     JSONSchemaVisitor,
     XmlSchemaVisitor,
@@ -50,6 +53,7 @@ module.exports = {
     ProtobufVisitor,
     OpenApiVisitor,
     AvroVisitor,
+    RustVisitor,
     InferFromJsonSchema,
     formats: {
         golang: GoLangVisitor,
@@ -65,6 +69,7 @@ module.exports = {
         markdown: MarkdownVisitor,
         protobuf: ProtobufVisitor,
         openapi: OpenApiVisitor,
-        avro: AvroVisitor
+        avro: AvroVisitor,
+        rust: RustVisitor
     }
 };
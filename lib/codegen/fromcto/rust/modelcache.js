@@ -0,0 +1,65 @@
+/*
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+'use strict';
+
+const fs = require('fs');
+const fse = require('fs-extra');
+const path = require('path');
+
+/**
+ * A directory of external models, one .cto file per namespace, used to
+ * resolve the imports of a model without downloading it again.
+ * @private
+ * @class
+ * @memberof module:concerto-codegen
+ */
+class ModelCache {
+    /**
+     * Create the ModelCache.
+     * @param {string} cacheDirectory - the directory of the model cache
+     */
+    constructor(cacheDirectory) {
+        this.cacheDirectory = cacheDirectory;
+    }
+
+    /**
+     * Adds the models of the cache directory to a model manager, without
+     * validating them. A missing directory holds no models.
+     * @param {ModelManager} modelManager - the model manager
+     */
+    load(modelManager) {
+        if (!fs.existsSync(this.cacheDirectory)) {
+            return;
+        }
+        fs.readdirSync(this.cacheDirectory).filter(file => file.endsWith('.cto')).sort().forEach(file => {
+            const filePath = path.join(this.cacheDirectory, file);
+            modelManager.addCTOModel(fs.readFileSync(filePath, 'utf8'), filePath, true);
+        });
+    }
+
+    /**
+     * Saves the external models of a model manager to the cache directory,
+     * which is created if needed.
+     * @param {ModelManager} modelManager - the model manager
+     */
+    save(modelManager) {
+        fse.ensureDirSync(this.cacheDirectory);
+        modelManager.getModelFiles().filter(modelFile => modelFile.isExternal()).forEach(modelFile => {
+            fs.writeFileSync(path.join(this.cacheDirectory, `${modelFile.getNamespace()}.cto`), modelFile.getDefinitions());
+        });
+    }
+}
+
+module.exports = ModelCache;
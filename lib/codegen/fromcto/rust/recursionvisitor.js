@@ -18,13 +18,24 @@ const debug = require('debug')('concerto-core:recursiondetectionvisitor');
 const util = require('util');
 
 /**
- * Detects whether ClassDeclaration contains recursive references.
+ * Detects whether ClassDeclaration contains recursive references that
+ * require indirection in Rust.
  * Basic example:
  * concept Person {
- *   o Person[] children
+ *   o Person child optional
  * }
  *
- * parameters.stack should be initialized to []
+ * Array fields are ignored, as a Vec already provides the indirection.
+ * Fields typed by a class with subclasses are followed into every
+ * assignable class, as they hold the union of the hierarchy.
+ *
+ * Only the cycles that close on the class at the bottom of the stack, or
+ * on one of its subclasses, which inherit its fields, are detected. Other
+ * cycles are broken by the fields that they contain.
+ *
+ * parameters.stack should be initialized to [fullyQualifiedName] of the
+ * class being checked, and parameters.roots to the fully qualified names
+ * of the classes assignable to it, which default to the class itself
  * @private
  * @class
  * @memberof module:concerto-codegen
@@ -92,20 +103,26 @@ class RecursionDetectionVisitor {
      */
     visitField(field, parameters) {
         debug('entering visitField', field.getName());
-        if (field.isPrimitive()) {
+        if (field.isPrimitive() || field.isArray()) {
             return false;
         }
 
         let type = field.getParent().getModelFile().
             getModelManager().getType(field.getFullyQualifiedTypeName());
+        const types = type.isClassDeclaration?.() ? type.getAssignableClassDeclarations() : [type];
 
         debug('stack', parameters.stack);
-        if (parameters.stack.includes(type.getFullyQualifiedName())) {
-            return true;
-        }
-        else {
-            return this.visit(type, parameters);
-        }
+        const roots = parameters.roots ?? parameters.stack.slice(0, 1);
+        return types.some(assignableType => {
+            const fqn = assignableType.getFullyQualifiedName();
+            if (roots.includes(fqn)) {
+                return true;
+            }
+            if (parameters.stack.includes(fqn)) {
+                return false;
+            }
+            return this.visit(assignableType, parameters);
+        });
     }
 
     /**
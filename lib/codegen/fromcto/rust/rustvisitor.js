@@ -14,37 +14,123 @@
 
 'use strict';
 const ModelUtil = require('@accordproject/concerto-core').ModelUtil;
-const { Console } = require('console');
+const Writer = require('@accordproject/concerto-util').Writer;
+const debug = require('debug')('concerto-codegen:rustvisitor');
 const util = require('util');
 const RecursionDetectionVisitor = require('./recursionvisitor');
+const EmptyPlugin = require('./emptyplugin');
+const ModelCache = require('./modelcache');
 
 // Rust keywords
 const keywords = [
-    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate", "do", "dyn",
-    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop", "macro",
-    "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "self", "static", "struct",
-    "super", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where",
-    "while", "yield",
+    'abstract', 'as', 'async', 'await', 'become', 'box', 'break', 'const', 'continue', 'crate', 'do', 'dyn',
+    'else', 'enum', 'extern', 'false', 'final', 'fn', 'for', 'if', 'impl', 'in', 'let', 'loop', 'macro',
+    'match', 'mod', 'move', 'mut', 'override', 'priv', 'pub', 'ref', 'return', 'self', 'static', 'struct',
+    'super', 'trait', 'true', 'try', 'type', 'typeof', 'unsafe', 'unsized', 'use', 'virtual', 'where',
+    'while', 'yield',
 ];
 
 // Valid characters for Rust names.
-const validChars = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_";
+const validChars = 'abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_';
+
+// Default Rust types for the Concerto primitive types, except DateTime, whose
+// type depends on the time library.
+const primitiveTypes = {
+    Boolean: 'bool',
+    Double: 'f64',
+    Integer: 'i32',
+    Long: 'i64',
+    String: 'String',
+};
+
+// Names that imported types must not shadow in a generated module.
+const reservedNames = [
+    'Box', 'Class', 'ConcertoClass', 'DateTime', 'Deserialize', 'EnumError', 'FixedOffset', 'Identifiable', 'OffsetDateTime',
+    'Option', 'Relationship', 'RelationshipError', 'Result', 'Serialize', 'String', 'Utc',
+    'ValidationError', 'Vec',
+];
+
+// The names exported by the utils module that a generated module can use,
+// unless qualified by a path. A module only imports the utils module if it
+// uses one of them, or implements ConcertoClass to use a CLASS constant.
+const utilsNames = /(?<!::)\b(ConcertoClass|EnumError|Identifiable|Relationship|RelationshipError|ValidationError|default_timestamp|(de)?serialize_datetime\w*|format_datetime|parse_datetime|validate_(length|range|regex))\b|::CLASS\b/;
+
+// The traits that every generated type implements, whatever the configured derives.
+const baseDerives = ['Debug', 'Serialize', 'Deserialize', 'Default'];
+
+// The derivable standard traits, which the relationships implement but for Copy.
+// Strings do not implement Copy, and floating point numbers Eq, Hash and Ord.
+const standardDerives = ['Clone', 'Copy', 'PartialEq', 'Eq', 'PartialOrd', 'Ord', 'Hash'];
+
+// The supertraits of the derivable standard traits, which a type must also derive.
+const requiredDerives = {
+    Copy: ['Clone'],
+    Eq: ['PartialEq'],
+    PartialOrd: ['PartialEq'],
+    Ord: ['Eq', 'PartialOrd'],
+};
+
+// The time libraries that can represent a Concerto DateTime, by the name used
+// in parameters.timeLibrary. The chrono-fixed-offset library keeps the offset
+// of the values it reads, while the others convert them to UTC. The literal
+// imports are used by the modules that write DateTime literals.
+const timeLibraries = {
+    chrono: {
+        type: 'DateTime<Utc>',
+        imports: 'use chrono::{ DateTime, Utc };',
+        literalImports: 'use chrono::{ DateTime, TimeZone, Utc };',
+        utilsImports: 'use chrono::{ DateTime, NaiveDateTime, SecondsFormat, Utc };',
+        dependency: 'chrono = { version = "=0.4.45", features = ["serde"] }',
+        hasDefault: true,
+    },
+    'chrono-fixed-offset': {
+        type: 'DateTime<FixedOffset>',
+        imports: 'use chrono::{ DateTime, FixedOffset };',
+        literalImports: 'use chrono::{ DateTime, FixedOffset, TimeZone };',
+        utilsImports: 'use chrono::{ DateTime, FixedOffset, NaiveDateTime, SecondsFormat, Utc };',
+        dependency: 'chrono = { version = "=0.4.45", features = ["serde"] }',
+        hasDefault: true,
+    },
+    time: {
+        type: 'OffsetDateTime',
+        imports: 'use time::OffsetDateTime;',
+        literalImports: 'use time::OffsetDateTime;',
+        utilsImports: 'use time::{ format_description::well_known::Rfc3339, OffsetDateTime, UtcOffset };',
+        dependency: 'time = { version = "=0.3.55", features = ["parsing"] }',
+        hasDefault: false,
+    },
+};
+
+// Dependencies of the Cargo.toml of a generated crate, besides the time library.
+// They are pinned to the versions that the generated code is tested with.
+const cargoDependencies = [
+    'regex = "=1.13.1"',
+    'serde = { version = "=1.0.229", features = ["derive"] }',
+    'serde_json = "=1.0.154"',
+];
 
 /**
  * Convert the contents of a ModelManager to Rust code.
  * All generated modules are referenced from the 'lib' package
  * with all generated modules in the same file system folder.
+ * Set the plugin property to an AbstractRustPlugin to extend the generated modules.
  *
  * @private
  * @class
- * @memberof module:concerto-tools
+ * @memberof module:concerto-codegen
  */
 class RustVisitor {
+    /**
+     * Create the RustVisitor.
+     */
+    constructor() {
+        this.plugin = new EmptyPlugin();
+    }
 
     /**
      * Helper method: Convert any string into a valid Rust name.
-     * @param {string} input 
-     * @returns {string}
+     * @param {string} input - the string to convert
+     * @returns {string} the snake case Rust name
      */
     toValidRustName(input) {
 
@@ -72,29 +158,76 @@ class RustVisitor {
     }
 
     /**
-     * Helper method: Converts a Concerto namespace to a Rust crate name.
-     * @param {string} namespace  - the concerto type
-     * @return {string} the corresponding package name in Rust
+     * Helper method: Converts a Concerto namespace to the names of the nested
+     * Rust modules generated for it. A namespace is a single flat module,
+     * unless nested modules are enabled, in which case each part of the
+     * namespace name is a module and the version is the innermost module,
+     * e.g. org::acme::hr::v1_0_0.
+     * @param {string} namespace - the Concerto namespace
+     * @param {Object} parameters - the parameter
+     * @return {string[]} the names of the modules, outermost first
+     * @private
+     */
+    toRustModuleSegments(namespace, parameters) {
+        if (!parameters?.nestedModules) {
+            return [this.toValidRustName(namespace)];
+        }
+        const { name, version } = ModelUtil.parseNamespace(namespace);
+        const segments = name.split('.').map(segment => this.toValidRustName(segment));
+        if (version) {
+            segments.push(this.toValidRustName(`v${version}`));
+        }
+        return segments;
+    }
+
+    /**
+     * Returns the names of the modules directly contained in a generated
+     * module, in the order of the namespaces.
+     * @param {string[]} namespaces - the Concerto namespaces
+     * @param {string[]} segments - the names of the containing module, empty for the module root
+     * @param {Object} parameters - the parameter
+     * @return {string[]} the names of the child modules
      * @private
      */
-    toRustCrateName(namespace) {
-        return namespace.replace(/@/g, '_').replace(/\./g, '_');
+    getChildModules(namespaces, segments, parameters) {
+        const children = new Set();
+        for (const namespace of namespaces) {
+            const namespaceSegments = this.toRustModuleSegments(namespace, parameters);
+            if (namespaceSegments.length > segments.length &&
+                segments.every((segment, index) => namespaceSegments[index] === segment)) {
+                children.add(namespaceSegments[segments.length]);
+            }
+        }
+        return [...children];
     }
 
     /**
-     * Returns true if the class declaration contains recursive references.
+     * Returns true if a field must be boxed because its type contains, directly
+     * or through other types, a reference back to the class of the field, or
+     * to one of its subclasses. A field that only leads to a cycle between
+     * other classes is not boxed, as the cycle is boxed at its own fields.
+     * Array fields never need to be boxed, as a Vec already provides the
+     * indirection.
      *
      * Basic example:
      * concept Person {
-     *   o Person[] children
+     *   o Person child optional
      * }
      *
-     * @param {object} classDeclaration the class being visited
-     * @returns {boolean} true if the model is recursive
+     * @param {Field} field the field
+     * @returns {boolean} true if the field is recursive
+     * @private
      */
-    isModelRecursive(classDeclaration) {
+    isFieldRecursive(field) {
+        if (field.isPrimitive() || field.isArray() || field.isTypeScalar?.()) {
+            return false;
+        }
         const visitor = new RecursionDetectionVisitor();
-        return classDeclaration.accept(visitor, { stack: [] });
+        const classDeclaration = field.getParent();
+        return !!field.accept(visitor, {
+            stack: [classDeclaration.getFullyQualifiedName()],
+            roots: classDeclaration.getAssignableClassDeclarations().map(declaration => declaration.getFullyQualifiedName()),
+        });
     }
 
     /**
@@ -113,8 +246,6 @@ class RustVisitor {
             return this.visitEnumDeclaration(thing, parameters);
         } else if (thing.isClassDeclaration?.()) {
             return this.visitClassDeclaration(thing, parameters);
-        } else if (thing.isTypeScalar?.()) {
-            return this.visitField(thing.getScalarField(), parameters);
         } else if (thing.isField?.()) {
             return this.visitField(thing, parameters);
         } else if (thing.isRelationship?.()) {
@@ -132,23 +263,72 @@ class RustVisitor {
      * Visitor design pattern
      * @param {ModelManager} modelManager - the object being visited
      * @param {Object} parameters - the parameter
+     * @param {Object} [parameters.primitiveTypes] - overrides of the Rust types used for Concerto primitives
+     * @param {boolean} [parameters.cargo] - write a complete Cargo crate, with a Cargo.toml and the modules under src
+     * @param {string} [parameters.crateName] - the name of the Cargo crate, defaults to concerto_model
+     * @param {string} [parameters.crateVersion] - the version of the Cargo crate, defaults to 0.1.0
+     * @param {string} [parameters.moduleRoot] - the path of the module containing the generated modules,
+     * e.g. crate::model, defaults to crate for a Cargo crate and to crate::lib otherwise
+     * @param {boolean} [parameters.nestedModules] - generate a nested module for each part of a namespace
+     * and its version, e.g. org::acme::hr::v1_0_0, instead of a single module per namespace
+     * @param {Object} [parameters.derives] - the traits to derive in addition to Debug, Serialize and
+     * Deserialize, by kind of type, e.g. { struct: ['Clone', 'PartialEq'], enum: ['Clone', 'Copy', 'PartialEq'],
+     * scalar: ['Clone', 'PartialEq'] }. The kinds are struct, enum and scalar, and unions derive the traits
+     * of structs. A struct or a union only derives the traits that the types of all of its fields implement.
+     * @param {Object} [parameters.attributes] - the attributes to write on the types of each kind,
+     * e.g. { struct: ['#[serde(deny_unknown_fields)]'] }
+     * @param {string} [parameters.timeLibrary] - the library of the DateTime type: chrono for
+     * DateTime<Utc>, the default, chrono-fixed-offset for DateTime<FixedOffset>, which keeps the
+     * offset of the values it reads, or time for OffsetDateTime in UTC
      * @return {Object} the result of visiting or null
      * @private
      */
     visitModelManager(modelManager, parameters) {
-        console.log('entering visitModelManager');
+        debug('entering visitModelManager');
+
+        // Every imported namespace is generated into the crate, so external models must be resolved first.
+        const unresolvedImports = this.getUnresolvedImports(modelManager);
+        if (unresolvedImports.length > 0) {
+            const descriptions = unresolvedImports.map(({ importer, namespace, uri }) =>
+                `${namespace}${uri ? ` from ${uri}` : ''} (imported by ${importer})`);
+            throw new Error(`Unresolved imports: ${descriptions.join(', ')}. Add the models to the model manager, or call resolveExternalModels, before generating Rust code.`);
+        }
+
+        if (parameters.cargo) {
+            this.addCargoManifest(parameters);
+        }
 
-        // Create the "lib.rs" file containing the module references.
-        const fileName = `mod.rs`;
-        parameters.fileWriter.openFile(fileName);
-        for (const namespace of modelManager.getNamespaces()) {
-            const namespaceFileName = this.toValidRustName(namespace);
-            parameters.fileWriter.writeLine(0, `pub mod ${namespaceFileName};`);
+        // Create the "lib.rs" or "mod.rs" file containing the module references.
+        const fileName = parameters.cargo ? 'lib.rs' : 'mod.rs';
+        const namespaces = modelManager.getNamespaces();
+        parameters.fileWriter.openFile(this.toSourceFilePath(fileName, parameters));
+        for (const module of this.getChildModules(namespaces, [], parameters)) {
+            parameters.fileWriter.writeLine(0, `pub mod ${module};`);
         }
-        parameters.fileWriter.writeLine(0, `pub mod utils;`);
+        parameters.fileWriter.writeLine(0, 'pub mod utils;');
         parameters.fileWriter.closeFile();
 
-        this.addUtilsModelFile(parameters)
+        // Create the intermediate modules of nested modules that are not namespaces.
+        const namespacePaths = namespaces.map(namespace => this.toRustModuleSegments(namespace, parameters).join('/'));
+        const intermediatePaths = new Set();
+        namespaces.forEach(namespace => {
+            const segments = this.toRustModuleSegments(namespace, parameters);
+            for (let length = 1; length < segments.length; length++) {
+                const path = segments.slice(0, length).join('/');
+                if (!namespacePaths.includes(path)) {
+                    intermediatePaths.add(path);
+                }
+            }
+        });
+        intermediatePaths.forEach(path => {
+            parameters.fileWriter.openFile(this.toSourceFilePath(`${path}/mod.rs`, parameters));
+            for (const module of this.getChildModules(namespaces, path.split('/'), parameters)) {
+                parameters.fileWriter.writeLine(0, `pub mod ${module};`);
+            }
+            parameters.fileWriter.closeFile();
+        });
+
+        this.addUtilsModelFile(parameters);
 
         // Create the files for each namespace.
         modelManager.getModelFiles(true).forEach((modelFile) => {
@@ -166,45 +346,205 @@ class RustVisitor {
      * @private
      */
     visitModelFile(modelFile, parameters) {
-        console.log('entering visitModelFile', modelFile.getNamespace());
+        debug('entering visitModelFile', modelFile.getNamespace());
 
         // Create the file for the namespace with a valid Rust name.
-        const fileName = this.toValidRustName(modelFile.getNamespace());
-        parameters.fileWriter.openFile(`${fileName}.rs`);
+        const segments = this.toRustModuleSegments(modelFile.getNamespace(), parameters);
+        const fileName = parameters.nestedModules ? `${segments.join('/')}/mod.rs` : `${segments[0]}.rs`;
+        parameters.fileWriter.openFile(this.toSourceFilePath(fileName, parameters));
 
-        // Add crate definition as first line in file. 
-        parameters.fileWriter.writeLine(0, `use serde::{ Deserialize, Serialize };`);
-        parameters.fileWriter.writeLine(0, `use chrono::{ DateTime, TimeZone, Utc };`);
-        parameters.fileWriter.writeLine(1, '');
+        // Declare the nested modules of the namespaces contained in this one.
+        if (parameters.nestedModules) {
+            const namespaces = modelFile.getModelManager().getNamespaces();
+            const childModules = this.getChildModules(namespaces, segments, parameters);
+            if (childModules.length > 0) {
+                childModules.forEach(module => parameters.fileWriter.writeLine(0, `pub mod ${module};`));
+                parameters.fileWriter.writeLine(1, '');
+            }
+        }
 
-        const relationshipImports = modelFile.getAllDeclarations()
-            .map(classDeclaration => {
-                return classDeclaration.getProperties().filter(property => property.isRelationship?.())
-            }).flatMap(property => property).map(property => property.getFullyQualifiedTypeName?.())
-
-        const imports = [...new Set([...modelFile.getImports(), ...relationshipImports])]
-        // Add imports.
-        imports.map(importString =>
-            ModelUtil.getNamespace(importString))
-            .filter(namespace => namespace !== modelFile.getNamespace()) // Skip own namespace.
-            .filter((v, i, a) => a.indexOf(v) === i) // Remove any duplicates from direct imports
-            .forEach(namespace => {
-                parameters.fileWriter.writeLine(0, `use crate::lib::${this.toValidRustName(namespace)}::*;`);
-            });
+        // Write the declarations first, so that only the imports used by the
+        // module are written before them.
+        const importedTypes = this.getImportedTypes(modelFile);
+        const body = new Writer();
+        const fileParameters = Object.assign({}, parameters, {
+            fileWriter: body,
+            namespace: modelFile.getNamespace(),
+            importedTypes: new Map(importedTypes.map(type => [`${type.namespace}.${type.name}`, type.alias])),
+        });
 
-        parameters.fileWriter.writeLine(0, `use crate::lib::utils::*;`);
+        // Add the use declarations of the plugin.
+        this.plugin.addFileImports(modelFile, fileParameters);
+        modelFile.getAllDeclarations().forEach(declaration => {
+            if (declaration.isEnum?.()) {
+                this.plugin.addEnumImports(declaration, fileParameters);
+            } else if (declaration.isClassDeclaration?.() && !declaration.isAbstract()) {
+                this.plugin.addClassImports(declaration, fileParameters);
+            }
+        });
 
-        parameters.fileWriter.writeLine(1, '');
+        body.writeLine(1, '');
 
         // Visit all of the asset and transaction declarations
         modelFile.getAllDeclarations().forEach((declaration) => {
-            declaration.accept(this, parameters);
+            declaration.accept(this, fileParameters);
+        });
+
+        this.plugin.addFileModules(modelFile, fileParameters);
+        const code = body.getBuffer();
+
+        // The serde traits are used by name, or to call their methods on a type.
+        const serdeTraits = ['Deserialize', 'Serialize']
+            .filter(trait => new RegExp(`(?<!::)\\b${trait}\\b|::${trait.toLowerCase()}\\(`).test(code));
+        if (serdeTraits.length > 0) {
+            parameters.fileWriter.writeLine(0, serdeTraits.length > 1 ? `use serde::{ ${serdeTraits.join(', ')} };` : `use serde::${serdeTraits[0]};`);
+        }
+        const timeLibrary = this.getTimeLibrary(parameters);
+        if (code.includes('.timestamp_millis_opt(')) {
+            parameters.fileWriter.writeLine(0, timeLibrary.literalImports);
+        } else if (code.includes(timeLibrary.type)) {
+            parameters.fileWriter.writeLine(0, timeLibrary.imports);
+        }
+        parameters.fileWriter.writeLine(1, '');
+
+        // Import the types referenced from other namespaces, aliased when their names collide.
+        importedTypes.forEach(({ namespace, name, alias }) => {
+            const path = `${this.toRustModulePath(namespace, parameters)}::${name}`;
+            parameters.fileWriter.writeLine(0, alias === name ? `use ${path};` : `use ${path} as ${alias};`);
         });
 
+        if (utilsNames.test(code)) {
+            parameters.fileWriter.writeLine(0, `use ${this.toModuleRoot(parameters)}::utils::*;`);
+        }
+        parameters.fileWriter.write(code);
         parameters.fileWriter.closeFile();
         return null;
     }
 
+    /**
+     * Returns the imports of the model files whose namespace is not in the
+     * model manager, such as external models that have not been resolved.
+     * Code is only generated when there are none; resolveExternalModels
+     * checks them to decide whether to load cached or download models.
+     * @param {ModelManager} modelManager - the model manager
+     * @return {Object[]} the importing namespace and the namespace and URI of each import
+     */
+    getUnresolvedImports(modelManager) {
+        const namespaces = modelManager.getNamespaces();
+        const unresolved = [];
+        modelManager.getModelFiles(true).forEach(modelFile => {
+            (modelFile.getAst().imports || []).forEach(imp => {
+                if (!namespaces.includes(imp.namespace)) {
+                    unresolved.push({ importer: modelFile.getNamespace(), namespace: imp.namespace, uri: imp.uri });
+                }
+            });
+        });
+        return unresolved;
+    }
+
+    /**
+     * Resolves the imports of the models of a model manager, so that Rust
+     * code can be generated for them. The models of the cache directory are
+     * added first; the imports that are still unresolved are then downloaded
+     * with updateExternalModels, unless offline, and the external models are
+     * saved to the cache directory.
+     * @param {ModelManager} modelManager - the model manager
+     * @param {Object} [options] - the options
+     * @param {string} [options.cacheDirectory] - the directory of the model cache, one .cto file per namespace
+     * @param {boolean} [options.offline] - never download external models
+     * @return {Promise<Object[]>} the imports that are still unresolved, as returned by getUnresolvedImports
+     */
+    async resolveExternalModels(modelManager, options = {}) {
+        const cache = options.cacheDirectory ? new ModelCache(options.cacheDirectory) : null;
+        if (cache && this.getUnresolvedImports(modelManager).length > 0) {
+            cache.load(modelManager);
+        }
+        if (!options.offline && this.getUnresolvedImports(modelManager).length > 0) {
+            await modelManager.updateExternalModels();
+            if (cache) {
+                cache.save(modelManager);
+            }
+        }
+        return this.getUnresolvedImports(modelManager);
+    }
+
+    /**
+     * Returns the types of other namespaces that are referenced by the
+     * generated module of a model file: the types of the fields of its
+     * structs and traits, or their unions. Types whose names collide with a
+     * local or another imported name get an alias prefixed by their
+     * namespace, e.g. State from org.accordproject.runtime as RuntimeState.
+     * @param {ModelFile} modelFile - the model file
+     * @return {Object[]} the namespace, name and alias of each imported type
+     * @private
+     */
+    getImportedTypes(modelFile) {
+        const localNames = new Set(reservedNames);
+        const imports = new Map();
+        modelFile.getAllDeclarations().forEach(declaration => {
+            localNames.add(declaration.getName());
+            if (!declaration.isClassDeclaration?.() || declaration.isEnum?.()) {
+                return;
+            }
+            if (this.hasTrait(declaration)) {
+                localNames.add(this.toTraitName(declaration));
+            }
+            if (this.hasUnion(declaration)) {
+                localNames.add(this.toUnionName(declaration));
+            }
+
+            // Abstract classes only reference the fields of their trait.
+            const properties = declaration.isAbstract() ? declaration.getOwnProperties() : declaration.getProperties();
+            properties.filter(property => !property.isPrimitive()).forEach(property => {
+                const fqn = property.getFullyQualifiedTypeName();
+                const namespace = ModelUtil.getNamespace(fqn);
+                if (namespace === modelFile.getNamespace()) {
+                    return;
+                }
+                const unionDeclaration = this.getUnionDeclaration(property);
+                const name = unionDeclaration ? this.toUnionName(unionDeclaration) : ModelUtil.getShortName(fqn);
+                imports.set(`${namespace}.${name}`, { namespace, name });
+            });
+        });
+
+        const types = [...imports.values()].sort((a, b) => `${a.namespace}.${a.name}`.localeCompare(`${b.namespace}.${b.name}`));
+        const collides = (type, name) => localNames.has(name) ||
+            types.some(other => other !== type && other.name === name);
+        types.forEach(type => {
+            type.alias = type.name;
+            if (collides(type, type.name)) {
+                const shortNamespace = ModelUtil.parseNamespace(type.namespace).name.split('.').pop();
+                type.alias = `${this.toUpperCamelCase(shortNamespace)}${type.name}`;
+                if (collides(type, type.alias) || types.some(other => other !== type && other.alias === type.alias)) {
+                    type.alias = `${this.toUpperCamelCase(type.namespace)}${type.name}`;
+                }
+            }
+        });
+        return types;
+    }
+
+    /**
+     * Returns the name that refers to a type in the module being generated:
+     * the type itself, its import alias or, if it is not imported, its
+     * fully qualified path.
+     * @param {Property} property - the property typed by the type
+     * @param {string} name - the name of the Rust type
+     * @param {Object} parameters - the parameter
+     * @return {string} the reference to the type
+     * @private
+     */
+    toTypeReference(property, name, parameters) {
+        if (!parameters?.namespace || property.isPrimitive()) {
+            return name;
+        }
+        const namespace = ModelUtil.getNamespace(property.getFullyQualifiedTypeName());
+        if (namespace === parameters.namespace) {
+            return name;
+        }
+        return parameters.importedTypes?.get(`${namespace}.${name}`) ??
+            `${this.toRustModulePath(namespace, parameters)}::${name}`;
+    }
+
     /**
      * Visitor design pattern
      * @param {ClassDeclaration} classDeclaration - the object being visited
@@ -213,195 +553,2247 @@ class RustVisitor {
      * @private
      */
     visitClassDeclaration(classDeclaration, parameters) {
-        console.log('entering visitClassDeclaration', classDeclaration.getName());
-        parameters.fileWriter.writeLine(0, '#[derive(Debug, Serialize, Deserialize)]')
+        debug('entering visitClassDeclaration', classDeclaration.getName());
+
+        // Abstract classes cannot be instantiated, so they have no struct,
+        // only a trait and a union of their concrete subclasses.
+        if (classDeclaration.isAbstract()) {
+            this.writeFieldDefaults(classDeclaration, parameters);
+            this.writeClassValidation(classDeclaration, parameters);
+            this.writeClassTrait(classDeclaration, parameters);
+            this.writeClassUnion(classDeclaration, parameters);
+            return null;
+        }
+
+        parameters.fileWriter.writeLine(0, `#[derive(${this.getDerives(classDeclaration, parameters).join(', ')})]`);
+        this.writeAttributes('struct', parameters);
+        this.plugin.addClassAttributes(classDeclaration, parameters);
         parameters.fileWriter.writeLine(0, `pub struct ${classDeclaration.getName()} {`);
 
-        this.visitField({
-            name: '$class',
-            type: 'String'
-        }, parameters)
+        // The $class tag is removed by serde before deserializing the variant of a union.
+        parameters.fileWriter.writeLine(1, '#[serde(');
+        parameters.fileWriter.writeLine(2, 'rename = "$class",');
+        parameters.fileWriter.writeLine(2, 'default,');
+        parameters.fileWriter.writeLine(1, ')]');
+        // The Class type is referenced by its path, as a model can declare a Class type.
+        parameters.fileWriter.writeLine(1, `pub _class: ${this.toModuleRoot(parameters)}::utils::Class<${classDeclaration.getName()}>,`);
 
-        classDeclaration.getProperties().forEach((property) => {
+        this.getStructProperties(classDeclaration).forEach((property) => {
             parameters.fileWriter.writeLine(1, '');
             property.accept(this, parameters);
         });
 
-        parameters.fileWriter.writeLine(0, `}`);
+        parameters.fileWriter.writeLine(0, '}');
         parameters.fileWriter.writeLine(0, '');
+
+        this.writeClassName(classDeclaration, parameters);
+        this.writeFieldDefaults(classDeclaration, parameters);
+        this.writeClassValidation(classDeclaration, parameters);
+        this.writeConstructors(classDeclaration, parameters);
+        this.writeDefault(classDeclaration, parameters);
+        this.writeClassTrait(classDeclaration, parameters);
+        this.writeTraitImplementations(classDeclaration, parameters);
+        this.writeIdentifiable(classDeclaration, parameters);
+        this.writeClassUnion(classDeclaration, parameters);
+        this.plugin.addClassImpls(classDeclaration, parameters);
+        this.plugin.addClassModules(classDeclaration, parameters);
         return null;
     }
 
     /**
-     * Visitor design pattern
-     * @param {ScalarDeclaration} scalarDeclaration - the object being visited
+     * Writes the implementation of the Identifiable trait for the struct of
+     * an identified class, returning the value of its identifier field.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
      * @param {Object} parameters - the parameter
-     * @return {Object} the result of visiting or null
      * @private
      */
-    visitScalarDeclaration(scalarDeclaration, parameters) {
-        console.log('entering visitScalarDeclaration', scalarDeclaration.getName());
-        parameters.fileWriter.writeLine(0, 'scalarDeclaration - ' + scalarDeclaration.getName());
-        return null;
+    writeIdentifiable(classDeclaration, parameters) {
+        if (!classDeclaration.isIdentified()) {
+            return;
+        }
+        parameters.fileWriter.writeLine(0, `impl Identifiable for ${classDeclaration.getName()} {`);
+        parameters.fileWriter.writeLine(1, 'fn fully_qualified_type_name(&self) -> &\'static str {');
+        parameters.fileWriter.writeLine(2, 'Self::CLASS');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, 'fn identifier(&self) -> &str {');
+        parameters.fileWriter.writeLine(2, `&${this.toIdentifierValue(classDeclaration)}`);
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
     }
 
     /**
-     * Visitor design pattern
-     * @param {Field} field - the object being visited
+     * Returns the expression of the identifier value of an identified class.
+     * Identifiers typed by a String scalar are wrapped in its newtype.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {string} the Rust expression, relative to self
+     * @private
+     */
+    toIdentifierValue(classDeclaration) {
+        const identifierFieldName = classDeclaration.getIdentifierFieldName();
+        const identifierField = classDeclaration.getProperties().find(property => property.getName() === identifierFieldName);
+        let value = '';
+        if (identifierField && this.getScalarValidator(identifierField)) {
+            value = '.value()';
+        } else if (identifierField?.isTypeScalar?.()) {
+            value = '.0';
+        }
+        return `self.${this.toRustFieldName(identifierFieldName)}${value}`;
+    }
+
+    /**
+     * Returns the properties of a class that are fields of its struct. The
+     * $identifier of an explicitly identified class is the value of its
+     * identifier field, so it is not a field of its own.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {Property[]} the properties
+     * @private
+     */
+    getStructProperties(classDeclaration) {
+        return classDeclaration.getProperties().filter(property =>
+            property.getName() !== '$identifier' || !classDeclaration.isExplicitlyIdentified());
+    }
+
+    /**
+     * Writes the ConcertoClass implementation of a struct, holding the fully
+     * qualified name of the Concerto type that its $class field is checked
+     * against.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
      * @param {Object} parameters - the parameter
-     * @return {Object} the result of visiting or null
      * @private
      */
-    visitField(field, parameters) {
+    writeClassName(classDeclaration, parameters) {
+        parameters.fileWriter.writeLine(0, `impl ConcertoClass for ${classDeclaration.getName()} {`);
+        parameters.fileWriter.writeLine(1, `const CLASS: &'static str = "${classDeclaration.getFullyQualifiedName()}";`);
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+    }
+
+    /**
+     * Writes the new function of a struct, taking its required fields, and a
+     * builder that sets its optional fields and checks the Concerto
+     * validators when it builds the struct. The $class and $timestamp system
+     * properties are filled in.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    writeConstructors(classDeclaration, parameters) {
+        const name = classDeclaration.getName();
+        const builderName = `${name}Builder`;
+        const properties = this.getStructProperties(classDeclaration);
+        const requiredProperties = properties.filter(property => !property.isOptional() && property.getName() !== '$timestamp');
+        const optionalProperties = properties.filter(property => property.isOptional());
+        const args = requiredProperties
+            .map(property => `${this.toRustFieldName(property.getName())}: ${this.toFieldRustType(property, parameters)}`)
+            .join(', ');
+        const argNames = requiredProperties.map(property => this.toRustFieldName(property.getName())).join(', ');
+        const writeAllow = () => {
+            if (requiredProperties.length > 7) {
+                parameters.fileWriter.writeLine(1, '#[allow(clippy::too_many_arguments)]');
+            }
+        };
 
+        parameters.fileWriter.writeLine(0, `impl ${name} {`);
+        parameters.fileWriter.writeLine(1, '/// Creates the struct from its required fields.');
+        writeAllow();
+        parameters.fileWriter.writeLine(1, `pub fn new(${args}) -> Self {`);
+        parameters.fileWriter.writeLine(2, 'Self {');
+        parameters.fileWriter.writeLine(3, '_class: Default::default(),');
+        properties.forEach(property => {
+            const fieldName = this.toRustFieldName(property.getName());
+            if (property.isOptional()) {
+                parameters.fileWriter.writeLine(3, `${fieldName}: None,`);
+            } else if (property.getName() === '$timestamp') {
+                parameters.fileWriter.writeLine(3, `${fieldName}: default_timestamp(),`);
+            } else {
+                parameters.fileWriter.writeLine(3, `${fieldName},`);
+            }
+        });
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, '/// Returns a builder of the struct from its required fields, to set its optional fields.');
+        writeAllow();
+        parameters.fileWriter.writeLine(1, `pub fn builder(${args}) -> ${builderName} {`);
+        parameters.fileWriter.writeLine(2, `${builderName} { value: Self::new(${argNames}) }`);
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
 
-        let type = this.toRustType(field.type)
-        if (field.isArray?.()) {
-            type = `Vec<${type}>`
+        parameters.fileWriter.writeLine(0, `/// Builder of \`${name}\`, which checks the Concerto validators of its fields.`);
+        parameters.fileWriter.writeLine(0, '#[derive(Debug)]');
+        parameters.fileWriter.writeLine(0, `pub struct ${builderName} {`);
+        parameters.fileWriter.writeLine(1, `value: ${name},`);
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, `impl ${builderName} {`);
+        const fieldNames = optionalProperties.map(property => this.toRustFieldName(property.getName()));
+        optionalProperties.forEach(property => {
+            const fieldName = this.toRustFieldName(property.getName());
+            const type = this.toFieldRustType(property, parameters).replace(/^Option<(.*)>$/, '$1');
+            parameters.fileWriter.writeLine(1, `/// Sets the optional \`${property.getName()}\` field.`);
+            parameters.fileWriter.writeLine(1, `pub fn ${this.toBuilderSetterName(fieldName, fieldNames)}(mut self, ${fieldName}: ${type}) -> Self {`);
+            parameters.fileWriter.writeLine(2, `self.value.${fieldName} = Some(${fieldName});`);
+            parameters.fileWriter.writeLine(2, 'self');
+            parameters.fileWriter.writeLine(1, '}');
+            parameters.fileWriter.writeLine(0, '');
+        });
+        parameters.fileWriter.writeLine(1, `/// Returns the built \`${name}\`, or the first validation error of its fields.`);
+        parameters.fileWriter.writeLine(1, `pub fn build(self) -> std::result::Result<${name}, ValidationError> {`);
+        parameters.fileWriter.writeLine(2, 'self.value.validate()?;');
+        parameters.fileWriter.writeLine(2, 'Ok(self.value)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+    }
+
+    /**
+     * Returns the name of the builder setter of an optional field, which is
+     * the field name unless it would collide with the build function: the
+     * setter of a \`build\` field is then prefixed with \`with_\`, as many
+     * times as needed to differ from the other setters.
+     * @param {string} fieldName - the Rust name of the field
+     * @param {string[]} fieldNames - the Rust names of the optional fields
+     * @return {string} the setter name
+     * @private
+     */
+    toBuilderSetterName(fieldName, fieldNames) {
+        if (fieldName !== 'build') {
+            return fieldName;
         }
+        let name = `with_${fieldName}`;
+        while (fieldNames.includes(name)) {
+            name = `with_${name}`;
+        }
+        return name;
+    }
 
-        parameters.fileWriter.writeLine(1, "#[serde(");
-        parameters.fileWriter.writeLine(2, `rename = "${field.name}",`)
-        if (field.isOptional?.()) {
-            parameters.fileWriter.writeLine(2, 'skip_serializing_if = "Option::is_none",')
-            type = `Option<${type}>`
+    /**
+     * Writes the Default implementation of a struct when all of its fields
+     * have a default: the modelled default if present, otherwise the default
+     * of their Rust type.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    writeDefault(classDeclaration, parameters) {
+        if (!this.hasDefault(classDeclaration, parameters) || this.isDefaultDerivable(classDeclaration, parameters)) {
+            return;
         }
-        if (this.isDateField(field.type)) {
-            if (field.isOptional?.()) {
-                parameters.fileWriter.writeLine(2, 'serialize_with = "serialize_datetime_option",')
-                parameters.fileWriter.writeLine(2, 'deserialize_with = "deserialize_datetime_option",')
+        parameters.fileWriter.writeLine(0, `impl Default for ${classDeclaration.getName()} {`);
+        parameters.fileWriter.writeLine(1, 'fn default() -> Self {');
+        parameters.fileWriter.writeLine(2, 'Self {');
+        parameters.fileWriter.writeLine(3, '_class: Default::default(),');
+        this.getStructProperties(classDeclaration).forEach(property => {
+            const fieldName = this.toRustFieldName(property.getName());
+            if (property.getName() === '$timestamp') {
+                parameters.fileWriter.writeLine(3, `${fieldName}: default_timestamp(),`);
+            } else if (this.toDefaultValue(property, parameters)) {
+                parameters.fileWriter.writeLine(3, `${fieldName}: ${this.toFieldFunctionPath('default', property, parameters)}(),`);
             } else {
-                parameters.fileWriter.writeLine(2, 'serialize_with = "serialize_datetime",')
-                parameters.fileWriter.writeLine(2, 'deserialize_with = "deserialize_datetime",')
+                parameters.fileWriter.writeLine(3, `${fieldName}: Default::default(),`);
+            }
+        });
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+    }
+
+    /**
+     * Returns true if the Default implementation of a struct is derived,
+     * which is the case when none of its fields has a modelled default or
+     * is a $timestamp.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} [parameters] - the parameter
+     * @return {boolean} true if the struct derives Default
+     * @private
+     */
+    isDefaultDerivable(classDeclaration, parameters) {
+        return this.hasDefault(classDeclaration, parameters) &&
+            this.getStructProperties(classDeclaration).every(property =>
+                property.getName() !== '$timestamp' && !this.toDefaultValue(property, parameters));
+    }
+
+    /**
+     * Returns true if the struct of a concrete class implements Default,
+     * which requires a default for each of its fields. Relationships, unions
+     * and enums only have a default when it is modelled, and so do DateTime
+     * fields if the time library has no default DateTime.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} [parameters] - the parameter
+     * @param {string[]} [visited] - the classes being checked, to stop at recursive fields
+     * @return {boolean} true if the struct implements Default
+     * @private
+     */
+    hasDefault(classDeclaration, parameters, visited = []) {
+        if (classDeclaration.isAbstract() || visited.includes(classDeclaration.getFullyQualifiedName())) {
+            return false;
+        }
+        const stack = [...visited, classDeclaration.getFullyQualifiedName()];
+        return this.getStructProperties(classDeclaration).every(property => {
+            if (property.isOptional() || property.isArray() || property.getName() === '$timestamp' ||
+                this.toDefaultValue(property, parameters)) {
+                return true;
+            }
+            if (property.isRelationship?.() || property.isTypeEnum?.() || property.isTypeScalar?.()) {
+                return false;
+            }
+            if (property.isPrimitive()) {
+                return !this.isDateField(property.getType()) || this.getTimeLibrary(parameters).hasDefault;
+            }
+            const declaration = property.getParent().getModelFile().getModelManager()
+                .getType(property.getFullyQualifiedTypeName());
+            return !this.hasUnion(declaration) && this.hasDefault(declaration, parameters, stack);
+        });
+    }
 
+    /**
+     * Returns the traits derived by the struct, enum or scalar newtype of a
+     * declaration: Debug, Serialize and Deserialize, followed by the traits
+     * configured for its kind that the type of every field implements, e.g.
+     * a struct with a Double field does not derive Eq, and a struct with a
+     * String field does not derive Copy.
+     * @param {ClassDeclaration|EnumDeclaration|ScalarDeclaration} declaration - the declaration
+     * @param {Object} [parameters] - the parameter
+     * @param {string[]} [visited] - the declarations being checked, to stop at recursive fields
+     * @return {string[]} the derived traits
+     * @private
+     */
+    getDerives(declaration, parameters, visited = []) {
+        let kind = 'struct';
+        let derives = ['Debug', 'Serialize', 'Deserialize'];
+        if (declaration.isEnum?.()) {
+            kind = 'enum';
+        } else if (declaration.isScalarDeclaration?.()) {
+            kind = 'scalar';
+            // Validated scalars deserialize through their constructor instead.
+            if (this.getValidator(declaration.getType(), declaration.getValidator())) {
+                derives = ['Debug', 'Serialize'];
             }
         }
-        parameters.fileWriter.writeLine(1, ")]");
-        parameters.fileWriter.writeLine(1, `pub ${this.toValidRustName(field.name.replace('$', '_'))}: ${type},`);
-        return null;
+        derives = [...new Set([...derives, ...(parameters?.derives?.[kind] ?? [])])];
+
+        // Structs derive Default, unless writeDefault implements it or they have none.
+        if (kind === 'struct') {
+            const derivesDefault = this.isDefaultDerivable(declaration, parameters);
+            derives = derives.filter(derive => !this.hasDerive([derive], 'Default'));
+            if (derivesDefault) {
+                derives.push('Default');
+            }
+        }
+
+        const fqn = declaration.getFullyQualifiedName?.();
+        if (kind === 'enum' || visited.includes(fqn)) {
+            return this.filterRequiredDerives(derives);
+        }
+        const stack = [...visited, fqn];
+        return this.filterRequiredDerives(derives.filter(derive => this.hasDerive(baseDerives, derive) ||
+            (kind === 'scalar'
+                ? this.isPrimitiveDerivable(declaration.getType(), derive, parameters)
+                : this.getStructProperties(declaration).every(property => this.isDerivable(property, derive, parameters, stack)))));
     }
 
     /**
-     * Visitor design pattern
-     * @param {EnumDeclaration} enumDeclaration - the object being visited
-     * @param {Object} parameters - the parameter
-     * @return {Object} the result of visiting or null
+     * Returns the traits derived by the union of a class: Debug and
+     * Deserialize, followed by the traits configured for structs that all
+     * of its variants derive. Serialize is implemented by the union itself
+     * and, as its variants hold data, it has no Default.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} [parameters] - the parameter
+     * @param {string[]} [visited] - the declarations being checked, to stop at recursive fields
+     * @return {string[]} the derived traits
      * @private
      */
-    visitEnumDeclaration(enumDeclaration, parameters) {
-        console.log('entering visitEnumDeclaration', enumDeclaration.getName());
-        parameters.fileWriter.writeLine(0, '#[derive(Debug, Serialize, Deserialize)]')
-        parameters.fileWriter.writeLine(0, 'pub enum ' + enumDeclaration.getName() + ' {');
+    getUnionDerives(classDeclaration, parameters, visited = []) {
+        const derives = [...new Set(['Debug', 'Deserialize', ...(parameters?.derives?.struct ?? [])])]
+            .filter(derive => derive !== 'Serialize' && !this.hasDerive([derive], 'Default'));
+        const concreteClasses = this.getConcreteClassDeclarations(classDeclaration);
+        return this.filterRequiredDerives(derives.filter(derive => this.hasDerive(baseDerives, derive) ||
+            concreteClasses.every(declaration => this.hasDerive(this.getDerives(declaration, parameters, visited), derive))));
+    }
 
-        enumDeclaration.getOwnProperties().forEach((property) => {
-            property.accept(this, parameters);
-        });
+    /**
+     * Removes the derived traits whose supertraits are not derived, e.g. Copy
+     * without Clone, or Ord once Eq has been removed.
+     * @param {string[]} derives - the derived traits
+     * @return {string[]} the derived traits that can be derived together
+     * @private
+     */
+    filterRequiredDerives(derives) {
+        const result = derives.filter(derive => (requiredDerives[this.toTraitBaseName(derive)] ?? [])
+            .every(required => this.hasDerive(derives, required)));
+        return result.length === derives.length ? result : this.filterRequiredDerives(result);
+    }
 
-        parameters.fileWriter.writeLine(0, '}\n');
-        return null;
+    /**
+     * Returns true if the Rust type of a property implements a derived trait.
+     * Vectors and boxes of recursive fields are not Copy, and neither are
+     * relationships, which implement the other standard traits only.
+     * @param {Property} property - the property
+     * @param {string} derive - the derived trait
+     * @param {Object} [parameters] - the parameter
+     * @param {string[]} [visited] - the declarations being checked, to stop at recursive fields
+     * @return {boolean} true if the trait is implemented
+     * @private
+     */
+    isDerivable(property, derive, parameters, visited = []) {
+        const trait = this.toTraitBaseName(derive);
+        if (trait === 'Copy' && (property.isArray() || property.isRelationship?.() || this.isFieldRecursive(property))) {
+            return false;
+        }
+        if (property.isRelationship?.()) {
+            return standardDerives.includes(trait);
+        }
+        if (property.isPrimitive()) {
+            return this.isPrimitiveDerivable(property.getType(), derive, parameters);
+        }
+        const unionDeclaration = this.getUnionDeclaration(property);
+        if (unionDeclaration) {
+            return this.hasDerive(this.getUnionDerives(unionDeclaration, parameters, visited), derive);
+        }
+        const declaration = property.getParent().getModelFile().getModelManager()
+            .getType(property.getFullyQualifiedTypeName());
+        return this.hasDerive(this.getDerives(declaration, parameters, visited), derive);
     }
 
     /**
-     * Visitor design pattern
-     * @param {EnumValueDeclaration} enumValueDeclaration - the object being visited
+     * Returns true if a list of derived traits includes a trait, whatever its path.
+     * @param {string[]} derives - the derived traits
+     * @param {string} derive - the trait
+     * @return {boolean} true if the trait is derived
+     * @private
+     */
+    hasDerive(derives, derive) {
+        return derives.some(other => this.toTraitBaseName(other) === this.toTraitBaseName(derive));
+    }
+
+    /**
+     * Returns true if the Rust type of a Concerto primitive implements a derived
+     * trait. Traits other than the standard ones are expected to be implemented.
+     * @param {string} type - the Concerto primitive type
+     * @param {string} derive - the derived trait
+     * @param {Object} [parameters] - the parameter
+     * @return {boolean} true if the trait is implemented
+     * @private
+     */
+    isPrimitiveDerivable(type, derive, parameters) {
+        switch (this.toTraitBaseName(derive)) {
+        case 'Copy':
+            return this.toRustType(type, parameters) !== 'String';
+        case 'Eq':
+        case 'Ord':
+        case 'Hash':
+            return !this.isFloatType(type, parameters);
+        default:
+            return true;
+        }
+    }
+
+    /**
+     * Returns true if the Rust type of a Concerto primitive is a floating point number.
+     * @param {string} type - the Concerto primitive type
+     * @param {Object} [parameters] - the parameter
+     * @return {boolean} true if the Rust type is f32 or f64
+     * @private
+     */
+    isFloatType(type, parameters) {
+        return /^f(32|64)$/.test(this.toRustType(type, parameters));
+    }
+
+    /**
+     * Returns the name of a trait without its path, e.g. Hash for std::hash::Hash.
+     * @param {string} trait - the trait
+     * @return {string} the name of the trait
+     * @private
+     */
+    toTraitBaseName(trait) {
+        return trait.split('::').pop();
+    }
+
+    /**
+     * Writes the attributes configured for a kind of type.
+     * @param {string} kind - struct, enum or scalar
      * @param {Object} parameters - the parameter
-     * @return {Object} the result of visiting or null
      * @private
      */
-    visitEnumValueDeclaration(enumValueDeclaration, parameters) {
-        console.log('entering visitEnumValueDeclaration', enumValueDeclaration.getName());
-        const name = enumValueDeclaration.getName();
-        parameters.fileWriter.writeLine(1, `${name},`);
-        return null;
+    writeAttributes(kind, parameters) {
+        (parameters.attributes?.[kind] ?? []).forEach(attribute => {
+            parameters.fileWriter.writeLine(0, attribute);
+        });
     }
 
     /**
-     * Visitor design pattern
-     * @param {RelationshipDeclaration} relationshipDeclaration - the object being visited
+     * Writes the functions returning the modelled defaults of the fields
+     * declared by a class, which serde calls for missing properties.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
      * @param {Object} parameters - the parameter
-     * @return {Object} the result of visiting or null
      * @private
      */
-    visitRelationshipDeclaration(relationshipDeclaration, parameters) {
-        console.log('entering visitRelationship', relationshipDeclaration.getName());
-        let type = relationshipDeclaration.type
+    writeFieldDefaults(classDeclaration, parameters) {
+        classDeclaration.getOwnProperties().forEach(property => {
+            const value = this.toDefaultValue(property, parameters);
+            if (!value) {
+                return;
+            }
+            parameters.fileWriter.writeLine(0, `pub fn ${this.toFieldFunctionName('default', property)}() -> ${this.toFieldRustType(property, parameters)} {`);
+            parameters.fileWriter.writeLine(1, property.isOptional() ? `Some(${value})` : value);
+            parameters.fileWriter.writeLine(0, '}');
+            parameters.fileWriter.writeLine(0, '');
+        });
+    }
 
-        if (relationshipDeclaration.isArray?.()) {
-            type = `Vec<${type}>`
+    /**
+     * Returns the Rust expression of the modelled default of a field, or null
+     * if it has none. Fields typed by a scalar use the default of the scalar.
+     * @param {Property} property - the property
+     * @param {Object} [parameters] - the parameter
+     * @return {string} the Rust expression, or null
+     * @private
+     */
+    toDefaultValue(property, parameters) {
+        if (!property.isField?.() || property.isArray()) {
+            return null;
         }
-
-        if (relationshipDeclaration.isOptional?.()) {
-            type = `Option<${type}>`
+        if (property.isTypeScalar?.()) {
+            const scalarField = property.getScalarField();
+            if (this.toRustLiteral(scalarField.getType(), scalarField.getDefaultValue(), parameters) === null) {
+                return null;
+            }
+            return `${this.toTypeReference(property, property.getType(), parameters)}::default()`;
         }
-
-        parameters.fileWriter.writeLine(1, `#[serde(rename = "${relationshipDeclaration.name}")]`);
-        parameters.fileWriter.writeLine(1, `pub ${this.toValidRustName(relationshipDeclaration.name.replace('$', ''))}: ${type},`);
-        return null;
+        const value = property.getDefaultValue?.();
+        if (value === null || value === undefined) {
+            return null;
+        }
+        if (property.isTypeEnum?.()) {
+            const enumDeclaration = property.getParent().getModelFile().getModelManager()
+                .getType(property.getFullyQualifiedTypeName());
+            const variant = this.getEnumVariants(enumDeclaration).get(value);
+            return `${this.toTypeReference(property, property.getType(), parameters)}::${variant}`;
+        }
+        return this.toRustLiteral(property.getType(), value, parameters);
     }
 
-    toRustType(type) {
+    /**
+     * Converts the default value of a Concerto primitive to a Rust expression.
+     * DateTime values without an offset are in UTC, as in Concerto, and keep
+     * their offset with the chrono-fixed-offset time library.
+     * @param {string} type - the Concerto primitive type
+     * @param {*} value - the default value
+     * @param {Object} [parameters] - the parameter
+     * @return {string} the Rust expression, or null if there is no valid default
+     * @private
+     */
+    toRustLiteral(type, value, parameters) {
+        if (value === null || value === undefined) {
+            return null;
+        }
         switch (type) {
-            case 'DateTime':
-                return 'DateTime<Utc>';
-            case 'Boolean':
-                return 'bool';
-            case 'Long':
-                return 'u64'
-            case 'Double':
-                return 'f64'
-            default: {
-                return type;
+        case 'String':
+            return `String::from(${this.toRustRawString(`${value}`)})`;
+        case 'Boolean':
+            return `${value}`;
+        case 'Integer':
+        case 'Long':
+        case 'Double':
+            return this.toRustNumber(value, this.toRustType(type, parameters));
+        case 'DateTime': {
+            const offset = `${value}`.match(/(Z|([+-])(\d\d):?(\d\d))$/i);
+            const millis = Date.parse(offset ? value : `${value}Z`);
+            if (isNaN(millis)) {
+                return null;
+            }
+            switch (this.getTimeLibrary(parameters)) {
+            case timeLibraries['chrono-fixed-offset']: {
+                const seconds = offset?.[2] ? Number(`${offset[2]}1`) * (offset[3] * 3600 + offset[4] * 60) : 0;
+                return `FixedOffset::east_opt(${seconds}).unwrap().timestamp_millis_opt(${millis}).unwrap()`;
+            }
+            case timeLibraries.time:
+                return `OffsetDateTime::from_unix_timestamp_nanos(${millis} * 1_000_000).unwrap()`;
+            default:
+                return `Utc.timestamp_millis_opt(${millis}).unwrap()`;
             }
         }
+        default:
+            return null;
+        }
     }
 
-    isDateField(type) {
-        return type === 'DateTime'
+    /**
+     * Returns the concrete classes that can be assigned to a class declaration,
+     * including the class itself unless it is abstract. Enums, which extend
+     * Concept, have no $class and are left out.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {ClassDeclaration[]} the concrete class declarations
+     * @private
+     */
+    getConcreteClassDeclarations(classDeclaration) {
+        return classDeclaration.getAssignableClassDeclarations()
+            .filter(declaration => !declaration.isAbstract() && !declaration.isEnum?.());
     }
 
-    addUtilsModelFile(parameters) {
-        parameters.fileWriter.openFile('utils.rs');
-        parameters.fileWriter.writeLine(0, 'use chrono::{ DateTime, TimeZone, Utc };')
-        parameters.fileWriter.writeLine(0, 'use serde::{ Deserialize, Serialize, Deserializer, Serializer };');
-        parameters.fileWriter.writeLine(1, '');
-        parameters.fileWriter.writeLine(0, 'pub fn serialize_datetime_option<S>(datetime: &Option<chrono::DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>')
-        parameters.fileWriter.writeLine(0, 'where')
-        parameters.fileWriter.writeLine(1, 'S: Serializer,')
-        parameters.fileWriter.writeLine(0, '{')
-        parameters.fileWriter.writeLine(1, 'match datetime {')
-        parameters.fileWriter.writeLine(2, 'Some(dt) => {')
-        parameters.fileWriter.writeLine(3, 'serialize_datetime(&dt, serializer)')
-        parameters.fileWriter.writeLine(2, '},')
-        parameters.fileWriter.writeLine(2, '_ => unreachable!(),')
-        parameters.fileWriter.writeLine(1, '}')
-        parameters.fileWriter.writeLine(0, '}')
-        parameters.fileWriter.writeLine(0, '')
-        parameters.fileWriter.writeLine(0, "pub fn deserialize_datetime_option<'de, D>(deserializer: D) -> Result<Option<chrono::DateTime<Utc>>, D::Error>")
-        parameters.fileWriter.writeLine(0, 'where')
-        parameters.fileWriter.writeLine(1, "D: Deserializer<'de>,")
-        parameters.fileWriter.writeLine(0, '{')
-        parameters.fileWriter.writeLine(1, 'match deserialize_datetime(deserializer) {')
-        parameters.fileWriter.writeLine(2, 'Ok(result)=>Ok(Some(result)),')
-        parameters.fileWriter.writeLine(2, 'Err(error) => Err(error),')
-        parameters.fileWriter.writeLine(1, '}')
-        parameters.fileWriter.writeLine(0, '}')
-        parameters.fileWriter.writeLine(0, '')
-        parameters.fileWriter.writeLine(0, "pub fn deserialize_datetime<'de, D>(deserializer: D) -> Result<chrono::DateTime<Utc>, D::Error>")
-        parameters.fileWriter.writeLine(0, 'where')
-        parameters.fileWriter.writeLine(1, "D: Deserializer<'de>,")
-        parameters.fileWriter.writeLine(0, '{')
-        parameters.fileWriter.writeLine(1, 'let datetime_str = String::deserialize(deserializer)?;')
-        parameters.fileWriter.writeLine(1, 'Utc.datetime_from_str(&datetime_str, "%Y-%m-%dT%H:%M:%S%.3f%Z").map_err(serde::de::Error::custom)')
-        parameters.fileWriter.writeLine(0, '}')
+    /**
+     * Returns true if a union enum is generated for the class declaration,
+     * which is the case for all classes with a trait. The union of an abstract
+     * class without concrete subclasses has no variants. As the system types,
+     * such as Concept or Participant, are the super types of every class of
+     * the models, their unions are only generated when a property refers to them.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {boolean} true if the class has a union
+     * @private
+     */
+    hasUnion(classDeclaration) {
+        if (!this.hasTrait(classDeclaration)) {
+            return false;
+        }
+        const modelFile = classDeclaration.getModelFile?.();
+        return !modelFile?.isSystemModelFile?.() || this.isTypeReferenced(classDeclaration);
+    }
+
+    /**
+     * Returns true if a property of a class of the model manager, a field or
+     * a relationship, is typed by a class declaration.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {boolean} true if the class is the type of a property
+     * @private
+     */
+    isTypeReferenced(classDeclaration) {
+        const fqn = classDeclaration.getFullyQualifiedName();
+        return classDeclaration.getModelFile().getModelManager().getModelFiles(true).some(modelFile =>
+            modelFile.getAllDeclarations().some(declaration => declaration.isClassDeclaration?.() && !declaration.isEnum?.() &&
+                declaration.getOwnProperties().some(property =>
+                    !property.isPrimitive() && property.getFullyQualifiedTypeName() === fqn)));
+    }
+
+    /**
+     * Returns the name of the union enum generated for a class declaration.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {string} the union name
+     * @private
+     */
+    toUnionName(classDeclaration) {
+        return `${classDeclaration.getName()}Union`;
+    }
+
+    /**
+     * Returns the class declaration whose union types a property, or null
+     * if the property is not typed by a class with subclasses.
+     * @param {Property} property - the property
+     * @return {ClassDeclaration} the class declaration or null
+     * @private
+     */
+    getUnionDeclaration(property) {
+        if (property.isPrimitive() || property.isTypeScalar?.()) {
+            return null;
+        }
+        const declaration = property.getParent().getModelFile().getModelManager()
+            .getType(property.getFullyQualifiedTypeName());
+        return declaration.isClassDeclaration?.() && this.hasUnion(declaration) ? declaration : null;
+    }
+
+    /**
+     * Writes the union of a class hierarchy: an enum tagged by $class with
+     * a variant for each concrete class, so that a property typed by a super
+     * type is deserialized to the right subtype. The variants are serialized
+     * as is, since their $class field already holds the tag. Its ConcertoClass
+     * implementation lists the classes that a relationship to it may refer to.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    writeClassUnion(classDeclaration, parameters) {
+        if (!this.hasUnion(classDeclaration)) {
+            return;
+        }
+        const name = this.toUnionName(classDeclaration);
+        const declarations = this.getConcreteClassDeclarations(classDeclaration);
+        const variants = declarations.map(declaration => {
+            const duplicate = declarations.some(other => other !== declaration && other.getName() === declaration.getName());
+            const variant = duplicate
+                ? `${declaration.getName()}${this.toUpperCamelCase(declaration.getNamespace())}`
+                : declaration.getName();
+            const type = declaration.getNamespace() === classDeclaration.getNamespace()
+                ? declaration.getName()
+                : `${this.toRustModulePath(declaration.getNamespace(), parameters)}::${declaration.getName()}`;
+            return { variant, type, fqn: declaration.getFullyQualifiedName() };
+        });
+
+        // A match on an empty enum must dereference it to be exhaustive.
+        const match = variants.length > 0 ? 'match self {' : 'match *self {';
+
+        parameters.fileWriter.writeLine(0, `#[derive(${this.getUnionDerives(classDeclaration, parameters).join(', ')})]`);
+        if (variants.length > 0) {
+            parameters.fileWriter.writeLine(0, '#[serde(tag = "$class")]');
+        }
+        parameters.fileWriter.writeLine(0, `pub enum ${name} {`);
+        variants.forEach(({ variant, type, fqn }) => {
+            parameters.fileWriter.writeLine(1, `#[serde(rename = "${fqn}")]`);
+            parameters.fileWriter.writeLine(1, `${variant}(${type}),`);
+        });
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+
+        parameters.fileWriter.writeLine(0, `impl ConcertoClass for ${name} {`);
+        parameters.fileWriter.writeLine(1, `const CLASS: &'static str = "${classDeclaration.getFullyQualifiedName()}";`);
+        parameters.fileWriter.writeLine(1, 'const CLASSES: &\'static [&\'static str] = &[');
+        variants.forEach(({ fqn }) => {
+            parameters.fileWriter.writeLine(2, `"${fqn}",`);
+        });
+        parameters.fileWriter.writeLine(1, '];');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+
+        parameters.fileWriter.writeLine(0, `impl ${name} {`);
+        parameters.fileWriter.writeLine(1, `/// Returns the variant as a \`dyn ${this.toTraitName(classDeclaration)}\` trait object.`);
+        parameters.fileWriter.writeLine(1, `pub fn as_dyn(&self) -> &dyn ${this.toTraitName(classDeclaration)} {`);
+        parameters.fileWriter.writeLine(2, match);
+        variants.forEach(({ variant }) => {
+            parameters.fileWriter.writeLine(3, `${name}::${variant}(value) => value,`);
+        });
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, '/// Checks the Concerto validators of the variant.');
+        parameters.fileWriter.writeLine(1, 'pub fn validate(&self) -> std::result::Result<(), ValidationError> {');
+        parameters.fileWriter.writeLine(2, match);
+        variants.forEach(({ variant }) => {
+            parameters.fileWriter.writeLine(3, `${name}::${variant}(value) => value.validate(),`);
+        });
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+
+        if (variants.length > 0 && classDeclaration.isIdentified()) {
+            parameters.fileWriter.writeLine(0, `impl Identifiable for ${name} {`);
+            parameters.fileWriter.writeLine(1, 'fn fully_qualified_type_name(&self) -> &\'static str {');
+            parameters.fileWriter.writeLine(2, 'match self {');
+            variants.forEach(({ variant }) => {
+                parameters.fileWriter.writeLine(3, `${name}::${variant}(value) => value.fully_qualified_type_name(),`);
+            });
+            parameters.fileWriter.writeLine(2, '}');
+            parameters.fileWriter.writeLine(1, '}');
+            parameters.fileWriter.writeLine(0, '');
+            parameters.fileWriter.writeLine(1, 'fn identifier(&self) -> &str {');
+            parameters.fileWriter.writeLine(2, 'match self {');
+            variants.forEach(({ variant }) => {
+                parameters.fileWriter.writeLine(3, `${name}::${variant}(value) => value.identifier(),`);
+            });
+            parameters.fileWriter.writeLine(2, '}');
+            parameters.fileWriter.writeLine(1, '}');
+            parameters.fileWriter.writeLine(0, '}');
+            parameters.fileWriter.writeLine(0, '');
+        }
+
+        parameters.fileWriter.writeLine(0, `impl Serialize for ${name} {`);
+        parameters.fileWriter.writeLine(1, `fn serialize<S>(&self, ${variants.length > 0 ? 'serializer' : '_serializer'}: S) -> std::result::Result<S::Ok, S::Error>`);
+        parameters.fileWriter.writeLine(1, 'where');
+        parameters.fileWriter.writeLine(2, 'S: serde::Serializer,');
+        parameters.fileWriter.writeLine(1, '{');
+        parameters.fileWriter.writeLine(2, match);
+        variants.forEach(({ variant }) => {
+            parameters.fileWriter.writeLine(3, `${name}::${variant}(value) => value.serialize(serializer),`);
+        });
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+    }
+
+    /**
+     * Returns true if a Rust trait is generated for the class declaration,
+     * which is the case for abstract classes and classes with subclasses.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {boolean} true if the class has a trait
+     * @private
+     */
+    hasTrait(classDeclaration) {
+        return classDeclaration.isAbstract() || classDeclaration.getDirectSubclasses().length > 0;
+    }
+
+    /**
+     * Returns the name of the Rust trait generated for a class declaration.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {string} the trait name
+     * @private
+     */
+    toTraitName(classDeclaration) {
+        return `I${classDeclaration.getName()}`;
+    }
+
+    /**
+     * Returns the path of the trait of a class declaration, as referenced
+     * from the module of a namespace.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {string} namespace - the namespace of the referencing module
+     * @param {Object} parameters - the parameter
+     * @return {string} the trait path
+     * @private
+     */
+    toTraitPath(classDeclaration, namespace, parameters) {
+        if (classDeclaration.getNamespace() === namespace) {
+            return this.toTraitName(classDeclaration);
+        }
+        return `${this.toRustModulePath(classDeclaration.getNamespace(), parameters)}::${this.toTraitName(classDeclaration)}`;
+    }
+
+    /**
+     * Writes the trait of an abstract or super type, with an accessor for each
+     * field declared by the class. The trait of the super type, if any, is a
+     * super trait, so that subtypes can be used polymorphically through it.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    writeClassTrait(classDeclaration, parameters) {
+        if (!this.hasTrait(classDeclaration)) {
+            return;
+        }
+        const superType = classDeclaration.getSuperTypeDeclaration();
+        const superTrait = superType ? `: ${this.toTraitPath(superType, classDeclaration.getNamespace(), parameters)}` : '';
+        const properties = classDeclaration.getOwnProperties();
+
+        if (properties.length === 0) {
+            parameters.fileWriter.writeLine(0, `pub trait ${this.toTraitName(classDeclaration)}${superTrait} {}`);
+        } else {
+            parameters.fileWriter.writeLine(0, `pub trait ${this.toTraitName(classDeclaration)}${superTrait} {`);
+            properties.forEach(property => {
+                parameters.fileWriter.writeLine(1, `fn ${this.toRustFieldName(property.getName())}(&self) -> &${this.toFieldRustType(property, parameters)};`);
+            });
+            parameters.fileWriter.writeLine(0, '}');
+        }
+        parameters.fileWriter.writeLine(0, '');
+    }
+
+    /**
+     * Writes the implementations of the traits of the class declaration
+     * and of all of its super types for the struct of the class.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    writeTraitImplementations(classDeclaration, parameters) {
+        const name = classDeclaration.getName();
+        const namespace = classDeclaration.getNamespace();
+        const traitDeclarations = [];
+        for (let declaration = classDeclaration; declaration; declaration = declaration.getSuperTypeDeclaration()) {
+            if (this.hasTrait(declaration)) {
+                traitDeclarations.push(declaration);
+            }
+        }
+
+        const structProperties = this.getStructProperties(classDeclaration);
+        traitDeclarations.forEach(declaration => {
+            const properties = declaration.getOwnProperties();
+            if (properties.length === 0) {
+                parameters.fileWriter.writeLine(0, `impl ${this.toTraitPath(declaration, namespace, parameters)} for ${name} {}`);
+            } else {
+                parameters.fileWriter.writeLine(0, `impl ${this.toTraitPath(declaration, namespace, parameters)} for ${name} {`);
+                properties.forEach((property, index) => {
+                    const fieldName = this.toRustFieldName(property.getName());
+                    if (index > 0) {
+                        parameters.fileWriter.writeLine(0, '');
+                    }
+                    const value = structProperties.includes(property)
+                        ? `self.${fieldName}`
+                        : this.toIdentifierValue(classDeclaration);
+                    parameters.fileWriter.writeLine(1, `fn ${fieldName}(&self) -> &${this.toFieldRustType(property, parameters)} {`);
+                    parameters.fileWriter.writeLine(2, `&${value}`);
+                    parameters.fileWriter.writeLine(1, '}');
+                });
+                parameters.fileWriter.writeLine(0, '}');
+            }
+            parameters.fileWriter.writeLine(0, '');
+        });
+    }
+
+    /**
+     * Writes the validate() method of a struct, which checks the validators
+     * of all of its fields, including the inherited ones, and validates the
+     * structs and unions that its fields hold.
+     * @param {ClassDeclaration} classDeclaration - the class being visited
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    writeValidateMethod(classDeclaration, parameters) {
+        const properties = classDeclaration.getProperties();
+
+        parameters.fileWriter.writeLine(0, `impl ${classDeclaration.getName()} {`);
+        parameters.fileWriter.writeLine(1, '/// Checks the Concerto validators declared on the fields of this type.');
+        parameters.fileWriter.writeLine(1, 'pub fn validate(&self) -> std::result::Result<(), ValidationError> {');
+        properties.filter(property => this.getFieldValidator(property)).forEach(field => {
+            const fieldName = this.toRustFieldName(field.getName());
+            parameters.fileWriter.writeLine(2, `${this.toFieldFunctionPath('validate', field, parameters)}(&self.${fieldName})?;`);
+        });
+        // Values of validated scalars are checked again, as their defaults
+        // are not validated.
+        properties.filter(property => this.getScalarValidator(property)).forEach(field => {
+            const fieldName = this.toRustFieldName(field.getName());
+            const scalar = this.toTypeReference(field, field.getType(), parameters);
+            let loop = null;
+            if (field.isArray() && field.isOptional()) {
+                loop = `for value in self.${fieldName}.iter().flatten() {`;
+            } else if (field.isArray()) {
+                loop = `for value in &self.${fieldName} {`;
+            } else if (field.isOptional()) {
+                loop = `if let Some(value) = &self.${fieldName} {`;
+            }
+            if (loop) {
+                parameters.fileWriter.writeLine(2, loop);
+                parameters.fileWriter.writeLine(3, `${scalar}::validate(value.value())?;`);
+                parameters.fileWriter.writeLine(2, '}');
+            } else {
+                parameters.fileWriter.writeLine(2, `${scalar}::validate(self.${fieldName}.value())?;`);
+            }
+        });
+        properties.filter(property => this.isClassField(property)).forEach(field => {
+            const fieldName = this.toRustFieldName(field.getName());
+            let loop = null;
+            if (field.isArray() && field.isOptional()) {
+                loop = `for value in self.${fieldName}.iter().flatten() {`;
+            } else if (field.isArray()) {
+                loop = `for value in &self.${fieldName} {`;
+            } else if (field.isOptional()) {
+                loop = `if let Some(value) = &self.${fieldName} {`;
+            }
+            if (loop) {
+                parameters.fileWriter.writeLine(2, loop);
+                parameters.fileWriter.writeLine(3, 'value.validate()?;');
+                parameters.fileWriter.writeLine(2, '}');
+            } else {
+                parameters.fileWriter.writeLine(2, `self.${fieldName}.validate()?;`);
+            }
+        });
+        parameters.fileWriter.writeLine(2, 'Ok(())');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+    }
+
+    /**
+     * Returns true if a property holds the struct or the union of a class,
+     * rather than a primitive, a scalar, an enum or a relationship.
+     * @param {Property} property - the property
+     * @return {boolean} true if the property holds a class
+     * @private
+     */
+    isClassField(property) {
+        return !property.isPrimitive() && !property.isRelationship?.() && !property.isTypeEnum?.() && !property.isTypeScalar?.();
+    }
+
+    /**
+     * Writes the validate() method of a concrete class, followed by the
+     * validation and deserialization functions for the validated fields
+     * declared by the class itself. Inherited fields reuse the functions
+     * written for their declaring class.
+     * @param {ClassDeclaration} classDeclaration - the class being visited
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    writeClassValidation(classDeclaration, parameters) {
+        if (!classDeclaration.isAbstract()) {
+            this.writeValidateMethod(classDeclaration, parameters);
+        }
+
+        classDeclaration.getOwnProperties()
+            .filter(property => this.getFieldValidator(property))
+            .forEach(field => {
+                const type = this.toFieldRustType(field, parameters);
+                const validateName = this.toFieldFunctionName('validate', field);
+                const path = `${classDeclaration.getName()}.${field.getName()}`;
+
+                parameters.fileWriter.writeLine(0, `pub fn ${validateName}(value: ${this.toFieldReferenceType(field, parameters)}) -> std::result::Result<(), ValidationError> {`);
+                if (field.isArray() && field.isOptional()) {
+                    parameters.fileWriter.writeLine(1, 'for value in value.iter().flatten() {');
+                    this.writeValidatorChecks(path, field.getType(), this.getFieldValidator(field), 'value', 2, parameters);
+                    parameters.fileWriter.writeLine(1, '}');
+                } else if (field.isArray()) {
+                    parameters.fileWriter.writeLine(1, 'for value in value {');
+                    this.writeValidatorChecks(path, field.getType(), this.getFieldValidator(field), 'value', 2, parameters);
+                    parameters.fileWriter.writeLine(1, '}');
+                } else if (field.isOptional()) {
+                    parameters.fileWriter.writeLine(1, 'if let Some(value) = value {');
+                    this.writeValidatorChecks(path, field.getType(), this.getFieldValidator(field), 'value', 2, parameters);
+                    parameters.fileWriter.writeLine(1, '}');
+                } else {
+                    this.writeValidatorChecks(path, field.getType(), this.getFieldValidator(field), 'value', 1, parameters);
+                }
+                parameters.fileWriter.writeLine(1, 'Ok(())');
+                parameters.fileWriter.writeLine(0, '}');
+                parameters.fileWriter.writeLine(0, '');
+
+                parameters.fileWriter.writeLine(0, `pub fn ${this.toFieldFunctionName('deserialize', field)}<'de, D>(deserializer: D) -> std::result::Result<${type}, D::Error>`);
+                parameters.fileWriter.writeLine(0, 'where');
+                parameters.fileWriter.writeLine(1, 'D: serde::Deserializer<\'de>,');
+                parameters.fileWriter.writeLine(0, '{');
+                parameters.fileWriter.writeLine(1, `let value = <${type}>::deserialize(deserializer)?;`);
+                parameters.fileWriter.writeLine(1, `${validateName}(&value).map_err(serde::de::Error::custom)?;`);
+                parameters.fileWriter.writeLine(1, 'Ok(value)');
+                parameters.fileWriter.writeLine(0, '}');
+                parameters.fileWriter.writeLine(0, '');
+            });
+    }
+
+    /**
+     * Visitor design pattern
+     * @param {ScalarDeclaration} scalarDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    visitScalarDeclaration(scalarDeclaration, parameters) {
+        debug('entering visitScalarDeclaration', scalarDeclaration.getName());
+
+        // Scalars become newtypes so that the scalar identity survives in Rust,
+        // while serializing exactly like the wrapped primitive.
+        const type = this.toRustType(scalarDeclaration.getType(), parameters);
+        const validator = this.getValidator(scalarDeclaration.getType(), scalarDeclaration.getValidator());
+        parameters.fileWriter.writeLine(0, `#[derive(${this.getDerives(scalarDeclaration, parameters).join(', ')})]`);
+        this.writeAttributes('scalar', parameters);
+        parameters.fileWriter.writeLine(0, '#[serde(transparent)]');
+        if (this.isDateField(scalarDeclaration.getType())) {
+            parameters.fileWriter.writeLine(0, `pub struct ${scalarDeclaration.getName()}(`);
+            parameters.fileWriter.writeLine(1, '#[serde(');
+            parameters.fileWriter.writeLine(2, 'serialize_with = "serialize_datetime",');
+            parameters.fileWriter.writeLine(2, 'deserialize_with = "deserialize_datetime",');
+            parameters.fileWriter.writeLine(1, ')]');
+            parameters.fileWriter.writeLine(1, `pub ${type},`);
+            parameters.fileWriter.writeLine(0, ');');
+        } else {
+            // The value of a validated scalar is private, so that it can only
+            // be created through new() or deserialization.
+            parameters.fileWriter.writeLine(0, `pub struct ${scalarDeclaration.getName()}(${validator ? '' : 'pub '}${type});`);
+        }
+        parameters.fileWriter.writeLine(0, '');
+
+        if (validator) {
+            this.writeScalarValidation(scalarDeclaration, validator, parameters);
+        }
+
+        const value = this.toRustLiteral(scalarDeclaration.getType(), scalarDeclaration.getDefaultValue?.(), parameters);
+        if (value) {
+            parameters.fileWriter.writeLine(0, `impl Default for ${scalarDeclaration.getName()} {`);
+            parameters.fileWriter.writeLine(1, 'fn default() -> Self {');
+            parameters.fileWriter.writeLine(2, `Self(${value})`);
+            parameters.fileWriter.writeLine(1, '}');
+            parameters.fileWriter.writeLine(0, '}');
+            parameters.fileWriter.writeLine(0, '');
+        }
+        return null;
+    }
+
+    /**
+     * Writes the validating constructor, accessors and Deserialize
+     * implementation of a scalar newtype.
+     * @param {ScalarDeclaration} scalarDeclaration - the scalar being visited
+     * @param {Object} validator - the validator constraints of the scalar
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    writeScalarValidation(scalarDeclaration, validator, parameters) {
+        const name = scalarDeclaration.getName();
+        const type = this.toRustType(scalarDeclaration.getType(), parameters);
+        const referenceType = type === 'String' ? '&str' : `&${type}`;
+
+        parameters.fileWriter.writeLine(0, `impl ${name} {`);
+        parameters.fileWriter.writeLine(1, `/// Creates a new ${name}, checking the Concerto validators of the scalar.`);
+        parameters.fileWriter.writeLine(1, `pub fn new(value: ${type}) -> std::result::Result<Self, ValidationError> {`);
+        parameters.fileWriter.writeLine(2, 'Self::validate(&value)?;');
+        parameters.fileWriter.writeLine(2, 'Ok(Self(value))');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, `/// Returns the value of the ${name}.`);
+        parameters.fileWriter.writeLine(1, `pub fn value(&self) -> ${referenceType} {`);
+        parameters.fileWriter.writeLine(2, '&self.0');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, `/// Consumes the ${name}, returning its value.`);
+        parameters.fileWriter.writeLine(1, `pub fn into_inner(self) -> ${type} {`);
+        parameters.fileWriter.writeLine(2, 'self.0');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, `/// Checks a value against the Concerto validators of ${name}.`);
+        parameters.fileWriter.writeLine(1, `pub fn validate(value: ${referenceType}) -> std::result::Result<(), ValidationError> {`);
+        this.writeValidatorChecks(name, scalarDeclaration.getType(), validator, 'value', 2, parameters);
+        parameters.fileWriter.writeLine(2, 'Ok(())');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, `impl<'de> Deserialize<'de> for ${name} {`);
+        parameters.fileWriter.writeLine(1, 'fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>');
+        parameters.fileWriter.writeLine(1, 'where');
+        parameters.fileWriter.writeLine(2, 'D: serde::Deserializer<\'de>,');
+        parameters.fileWriter.writeLine(1, '{');
+        parameters.fileWriter.writeLine(2, `let value = ${type}::deserialize(deserializer)?;`);
+        parameters.fileWriter.writeLine(2, 'Self::new(value).map_err(serde::de::Error::custom)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+    }
+
+    /**
+     * Writes the calls to the utils validation functions for a validator.
+     * @param {string} path - the name reported in validation errors
+     * @param {string} type - the Concerto primitive type being validated
+     * @param {Object} validator - the validator constraints
+     * @param {string} value - the Rust expression referencing the value
+     * @param {number} indent - the indentation level
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    writeValidatorChecks(path, type, validator, value, indent, parameters) {
+        const rustType = this.toRustType(type, parameters);
+        const toOption = (bound, literal) => bound === null || bound === undefined ? 'None' : `Some(${literal(bound)})`;
+
+        if (validator.regex) {
+            const pattern = this.toRustRawString(this.toRustRegex(validator.regex, path));
+            parameters.fileWriter.writeLine(indent, 'static REGEX: std::sync::OnceLock<std::result::Result<regex::Regex, regex::Error>> = std::sync::OnceLock::new();');
+            parameters.fileWriter.writeLine(indent, `validate_regex("${path}", ${value}, &REGEX, ${pattern})?;`);
+        }
+        if (validator.minLength !== undefined || validator.maxLength !== undefined) {
+            const minLength = toOption(validator.minLength, bound => `${bound}`);
+            const maxLength = toOption(validator.maxLength, bound => `${bound}`);
+            parameters.fileWriter.writeLine(indent, `validate_length("${path}", ${value}, ${minLength}, ${maxLength})?;`);
+        }
+        if (validator.lower !== undefined || validator.upper !== undefined) {
+            const lower = toOption(validator.lower, bound => this.toRustNumber(bound, rustType));
+            const upper = toOption(validator.upper, bound => this.toRustNumber(bound, rustType));
+            parameters.fileWriter.writeLine(indent, `validate_range("${path}", *${value}, ${lower}, ${upper})?;`);
+        }
+    }
+
+    /**
+     * Returns the validator constraints of a field, or null if the field is
+     * not constrained. Fields typed by a scalar are validated by the scalar.
+     * @param {Property} property - the property
+     * @return {Object} the validator constraints or null
+     * @private
+     */
+    getFieldValidator(property) {
+        if (!property.isField?.() || property.isTypeScalar?.() || !property.isPrimitive?.()) {
+            return null;
+        }
+        return this.getValidator(property.getType(), property.getValidator?.());
+    }
+
+    /**
+     * Returns the validator constraints of the scalar typing a field, or
+     * null if the field is not typed by a validated scalar.
+     * @param {Property} property - the property
+     * @return {Object} the validator constraints or null
+     * @private
+     */
+    getScalarValidator(property) {
+        if (!property.isField?.() || !property.isTypeScalar?.()) {
+            return null;
+        }
+        const scalarField = property.getScalarField();
+        return this.getValidator(scalarField.getType(), scalarField.getValidator?.());
+    }
+
+    /**
+     * Extracts the constraints of a Concerto validator: a regex and length
+     * bounds for Strings, or a range for numeric types.
+     * @param {string} type - the Concerto primitive type being validated
+     * @param {Object} validator - the Concerto validator
+     * @return {Object} the validator constraints or null
+     * @private
+     */
+    getValidator(type, validator) {
+        if (!validator) {
+            return null;
+        }
+        const result = {};
+        if (type === 'String') {
+            if (validator.getRegex?.()) {
+                result.regex = validator.getRegex();
+            }
+            const minLength = validator.getMinLength?.();
+            const maxLength = validator.getMaxLength?.();
+            if (minLength !== null && minLength !== undefined) {
+                result.minLength = minLength;
+            }
+            if (maxLength !== null && maxLength !== undefined) {
+                result.maxLength = maxLength;
+            }
+        } else if (['Integer', 'Long', 'Double'].includes(type)) {
+            const lower = validator.getLowerBound?.();
+            const upper = validator.getUpperBound?.();
+            if (lower !== null && lower !== undefined) {
+                result.lower = lower;
+            }
+            if (upper !== null && upper !== undefined) {
+                result.upper = upper;
+            }
+        }
+        return Object.keys(result).length > 0 ? result : null;
+    }
+
+    /**
+     * Visitor design pattern
+     * @param {Field} field - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    visitField(field, parameters) {
+
+
+        const unionDeclaration = this.getUnionDeclaration(field);
+        let type = unionDeclaration ? this.toUnionName(unionDeclaration) : this.toRustType(field.type, parameters);
+        type = this.toTypeReference(field, type, parameters);
+        if (this.isFieldRecursive(field)) {
+            type = `Box<${type}>`;
+        }
+        if (field.isArray?.()) {
+            type = `Vec<${type}>`;
+        }
+
+        parameters.fileWriter.writeLine(1, '#[serde(');
+        parameters.fileWriter.writeLine(2, `rename = "${field.name}",`);
+        if (field.isOptional?.()) {
+            parameters.fileWriter.writeLine(2, 'skip_serializing_if = "Option::is_none",');
+            type = `Option<${type}>`;
+        }
+        // DateTime scalars are newtypes that serialize their value themselves.
+        if (this.isDateField(field.type)) {
+            const suffix = `${field.isArray?.() ? '_vec' : ''}${field.isOptional?.() ? '_option' : ''}`;
+            // A field deserialized with a function is only optional in JSON with a default.
+            if (field.isOptional?.() && !this.toDefaultValue(field, parameters)) {
+                parameters.fileWriter.writeLine(2, 'default,');
+            }
+            parameters.fileWriter.writeLine(2, `serialize_with = "serialize_datetime${suffix}",`);
+            parameters.fileWriter.writeLine(2, `deserialize_with = "deserialize_datetime${suffix}",`);
+        }
+        if (field.name === '$timestamp') {
+            parameters.fileWriter.writeLine(2, 'default = "default_timestamp",');
+        } else if (this.toDefaultValue(field, parameters)) {
+            parameters.fileWriter.writeLine(2, `default = "${this.toFieldFunctionPath('default', field, parameters)}",`);
+        }
+        if (this.getFieldValidator(field)) {
+            if (field.isOptional() && !this.toDefaultValue(field, parameters)) {
+                parameters.fileWriter.writeLine(2, 'default,');
+            }
+            parameters.fileWriter.writeLine(2, `deserialize_with = "${this.toFieldFunctionPath('deserialize', field, parameters)}",`);
+        }
+        parameters.fileWriter.writeLine(1, ')]');
+        parameters.fileWriter.writeLine(1, `pub ${this.toRustFieldName(field.name)}: ${type},`);
+        return null;
+    }
+
+    /**
+     * Visitor design pattern
+     * @param {EnumDeclaration} enumDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    visitEnumDeclaration(enumDeclaration, parameters) {
+        debug('entering visitEnumDeclaration', enumDeclaration.getName());
+        parameters.fileWriter.writeLine(0, `#[derive(${this.getDerives(enumDeclaration, parameters).join(', ')})]`);
+        this.writeAttributes('enum', parameters);
+        this.plugin.addEnumAttributes(enumDeclaration, parameters);
+        parameters.fileWriter.writeLine(0, 'pub enum ' + enumDeclaration.getName() + ' {');
+
+        enumDeclaration.getOwnProperties().forEach((property) => {
+            property.accept(this, parameters);
+        });
+
+        parameters.fileWriter.writeLine(0, '}\n');
+        this.writeEnumImpls(enumDeclaration, parameters);
+        this.plugin.addEnumImpls(enumDeclaration, parameters);
+        this.plugin.addEnumModules(enumDeclaration, parameters);
+        return null;
+    }
+
+    /**
+     * Visitor design pattern
+     * @param {EnumValueDeclaration} enumValueDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    visitEnumValueDeclaration(enumValueDeclaration, parameters) {
+        debug('entering visitEnumValueDeclaration', enumValueDeclaration.getName());
+        const name = enumValueDeclaration.getName();
+        const variant = this.getEnumVariants(enumValueDeclaration.getParent()).get(name);
+        if (variant !== name) {
+            parameters.fileWriter.writeLine(1, `#[serde(rename = "${name}")]`);
+        }
+        parameters.fileWriter.writeLine(1, `${variant},`);
+        return null;
+    }
+
+    /**
+     * Writes the ALL constant and the as_str method of an enum, and its
+     * Display and FromStr implementations, which use the Concerto names of
+     * the values.
+     * @param {EnumDeclaration} enumDeclaration - the enum declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    writeEnumImpls(enumDeclaration, parameters) {
+        const name = enumDeclaration.getName();
+        const variants = [...this.getEnumVariants(enumDeclaration)];
+        parameters.fileWriter.writeLine(0, `impl ${name} {`);
+        parameters.fileWriter.writeLine(1, `pub const ALL: [${name}; ${variants.length}] = [`);
+        variants.forEach(([, variant]) => {
+            parameters.fileWriter.writeLine(2, `${name}::${variant},`);
+        });
+        parameters.fileWriter.writeLine(1, '];');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, 'pub fn as_str(&self) -> &\'static str {');
+        parameters.fileWriter.writeLine(2, 'match *self {');
+        variants.forEach(([value, variant]) => {
+            parameters.fileWriter.writeLine(3, `${name}::${variant} => "${value}",`);
+        });
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, `impl std::fmt::Display for ${name} {`);
+        parameters.fileWriter.writeLine(1, 'fn fmt(&self, f: &mut std::fmt::Formatter<\'_>) -> std::fmt::Result {');
+        parameters.fileWriter.writeLine(2, 'f.write_str(self.as_str())');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, `impl std::str::FromStr for ${name} {`);
+        parameters.fileWriter.writeLine(1, 'type Err = EnumError;');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, 'fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {');
+        parameters.fileWriter.writeLine(2, 'match value {');
+        variants.forEach(([value, variant]) => {
+            parameters.fileWriter.writeLine(3, `"${value}" => Ok(${name}::${variant}),`);
+        });
+        parameters.fileWriter.writeLine(3, `_ => Err(EnumError::new("${name}", value)),`);
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+    }
+
+    /**
+     * Returns the Rust variants of the values of an enum, by the names of the
+     * values. A variant that collides with Self or with a previous variant,
+     * e.g. Active for both ACTIVE and Active, gets an underscore suffix.
+     * @param {EnumDeclaration} enumDeclaration - the enum declaration
+     * @return {Map<string, string>} the variants by the names of the values
+     * @private
+     */
+    getEnumVariants(enumDeclaration) {
+        const variants = new Map();
+        enumDeclaration.getOwnProperties().forEach(property => {
+            let variant = this.toRustVariantName(property.getName());
+            while (variant === 'Self' || [...variants.values()].includes(variant)) {
+                variant += '_';
+            }
+            variants.set(property.getName(), variant);
+        });
+        return variants;
+    }
+
+    /**
+     * Converts the name of an enum value to an UpperCamelCase Rust variant,
+     * e.g. PassedTesting for PASSED_TESTING or passedTesting. Words in
+     * capitals are lowercased, and a name that does not start with a letter
+     * gets a V prefix.
+     * @param {string} name - the name of the enum value
+     * @return {string} the name of the variant
+     * @private
+     */
+    toRustVariantName(name) {
+        const variant = this.toUpperCamelCase(name.replace(/[A-Za-z0-9]+/g, word => /[a-z]/.test(word) ? word : word.toLowerCase()));
+        return /^[A-Za-z]/.test(variant) ? variant : `V${variant}`;
+    }
+
+    /**
+     * Visitor design pattern
+     * @param {RelationshipDeclaration} relationshipDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    visitRelationshipDeclaration(relationshipDeclaration, parameters) {
+        debug('entering visitRelationship', relationshipDeclaration.getName());
+        const unionDeclaration = this.getUnionDeclaration(relationshipDeclaration);
+        const target = unionDeclaration ? this.toUnionName(unionDeclaration) : relationshipDeclaration.type;
+        let type = `Relationship<${this.toTypeReference(relationshipDeclaration, target, parameters)}>`;
+
+        if (relationshipDeclaration.isArray?.()) {
+            type = `Vec<${type}>`;
+        }
+
+        parameters.fileWriter.writeLine(1, '#[serde(');
+        parameters.fileWriter.writeLine(2, `rename = "${relationshipDeclaration.name}",`);
+        if (relationshipDeclaration.isOptional?.()) {
+            parameters.fileWriter.writeLine(2, 'skip_serializing_if = "Option::is_none",');
+            type = `Option<${type}>`;
+        }
+        parameters.fileWriter.writeLine(1, ')]');
+        parameters.fileWriter.writeLine(1, `pub ${this.toValidRustName(relationshipDeclaration.name.replace('$', ''))}: ${type},`);
+        return null;
+    }
+
+    /**
+     * Converts a Concerto type to a Rust type. Primitive types use the
+     * defaults below unless they are overridden by parameters.primitiveTypes,
+     * e.g. { Long: 'i128' }. Other types keep their Concerto name.
+     * @param {string} type - the Concerto type
+     * @param {Object} [parameters] - the parameter
+     * @param {Object} [parameters.primitiveTypes] - overrides of the Rust types used for Concerto primitives
+     * @return {string} the Rust type
+     * @private
+     */
+    toRustType(type, parameters) {
+        const overrides = parameters?.primitiveTypes ?? {};
+        if (Object.prototype.hasOwnProperty.call(overrides, type)) {
+            return overrides[type];
+        }
+        if (Object.prototype.hasOwnProperty.call(primitiveTypes, type)) {
+            return primitiveTypes[type];
+        }
+        if (this.isDateField(type)) {
+            return this.getTimeLibrary(parameters).type;
+        }
+        return type;
+    }
+
+    /**
+     * Returns the time library of the DateTime type, chrono unless
+     * parameters.timeLibrary selects another one.
+     * @param {Object} [parameters] - the parameter
+     * @param {string} [parameters.timeLibrary] - the name of the time library
+     * @return {Object} the time library
+     * @private
+     */
+    getTimeLibrary(parameters) {
+        const name = parameters?.timeLibrary ?? 'chrono';
+        if (!Object.prototype.hasOwnProperty.call(timeLibraries, name)) {
+            throw new Error(`Unsupported time library: ${name}. Use one of ${Object.keys(timeLibraries).join(', ')}.`);
+        }
+        return timeLibraries[name];
+    }
+
+    /**
+     * Returns true if the Concerto type is a DateTime.
+     * @param {string} type - the Concerto type
+     * @return {boolean} true if the type is a DateTime
+     * @private
+     */
+    isDateField(type) {
+        return type === 'DateTime';
+    }
+
+    /**
+     * Returns the path of the Rust module that contains the generated modules.
+     * Unless configured, the modules of a Cargo crate are at its root and
+     * other modules are expected in a lib module.
+     * @param {Object} parameters - the parameter
+     * @return {string} the module path
+     * @private
+     */
+    toModuleRoot(parameters) {
+        if (parameters?.moduleRoot) {
+            return parameters.moduleRoot.replace(/::$/, '');
+        }
+        return parameters?.cargo ? 'crate' : 'crate::lib';
+    }
+
+    /**
+     * Returns the path of the Rust module generated for a namespace.
+     * @param {string} namespace - the Concerto namespace
+     * @param {Object} parameters - the parameter
+     * @return {string} the module path
+     * @private
+     */
+    toRustModulePath(namespace, parameters) {
+        return [this.toModuleRoot(parameters), ...this.toRustModuleSegments(namespace, parameters)].join('::');
+    }
+
+    /**
+     * Returns the path of a generated Rust source file, which is in the src
+     * folder of a Cargo crate.
+     * @param {string} fileName - the name of the file
+     * @param {Object} parameters - the parameter
+     * @return {string} the path of the file
+     * @private
+     */
+    toSourceFilePath(fileName, parameters) {
+        return parameters.cargo ? `src/${fileName}` : fileName;
+    }
+
+    /**
+     * Writes the Cargo.toml of a generated crate, with the dependencies
+     * used by the generated code.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    addCargoManifest(parameters) {
+        parameters.fileWriter.openFile('Cargo.toml');
+        parameters.fileWriter.writeLine(0, '[package]');
+        parameters.fileWriter.writeLine(0, `name = "${parameters.crateName || 'concerto_model'}"`);
+        parameters.fileWriter.writeLine(0, `version = "${parameters.crateVersion || '0.1.0'}"`);
+        parameters.fileWriter.writeLine(0, 'edition = "2021"');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '[dependencies]');
+        [this.getTimeLibrary(parameters).dependency, ...cargoDependencies].sort().forEach(dependency => {
+            parameters.fileWriter.writeLine(0, dependency);
+        });
+        parameters.fileWriter.closeFile();
+    }
+
+    /**
+     * Converts a string to an UpperCamelCase Rust identifier.
+     * @param {string} input - the string
+     * @return {string} the identifier
+     * @private
+     */
+    toUpperCamelCase(input) {
+        return input.split(/[^A-Za-z0-9]+/)
+            .filter(part => part)
+            .map(part => part.charAt(0).toUpperCase() + part.slice(1))
+            .join('');
+    }
+
+    /**
+     * Returns the name of the struct field generated for a Concerto property.
+     * @param {string} name - the Concerto property name
+     * @return {string} the Rust field name
+     * @private
+     */
+    toRustFieldName(name) {
+        return this.toValidRustName(name.replace('$', '_'));
+    }
+
+    /**
+     * Returns the Rust type of a field, including any Vec or Option wrapper.
+     * @param {Field} field - the field
+     * @param {Object} [parameters] - the parameter
+     * @return {string} the Rust type
+     * @private
+     */
+    toFieldRustType(field, parameters) {
+        const unionDeclaration = field.isField?.() || field.isRelationship?.() ? this.getUnionDeclaration(field) : null;
+        let type = unionDeclaration ? this.toUnionName(unionDeclaration) : this.toRustType(field.getType(), parameters);
+        type = this.toTypeReference(field, type, parameters);
+        if (field.isRelationship?.()) {
+            type = `Relationship<${type}>`;
+        } else if (field.isField?.() && this.isFieldRecursive(field)) {
+            type = `Box<${type}>`;
+        }
+        if (field.isArray()) {
+            type = `Vec<${type}>`;
+        }
+        if (field.isOptional()) {
+            type = `Option<${type}>`;
+        }
+        return type;
+    }
+
+    /**
+     * Returns the type used to borrow the value of a field in a function argument.
+     * @param {Field} field - the field
+     * @param {Object} [parameters] - the parameter
+     * @return {string} the Rust reference type
+     * @private
+     */
+    toFieldReferenceType(field, parameters) {
+        const type = this.toRustType(field.getType(), parameters);
+        if (field.isOptional()) {
+            return `&${this.toFieldRustType(field, parameters)}`;
+        } else if (field.isArray()) {
+            return `&[${type}]`;
+        } else if (type === 'String') {
+            return '&str';
+        }
+        return `&${type}`;
+    }
+
+    /**
+     * Returns the name of a generated function dedicated to a field,
+     * e.g. validate_person_first_name.
+     * @param {string} prefix - the function prefix
+     * @param {Field} field - the field
+     * @return {string} the function name
+     * @private
+     */
+    toFieldFunctionName(prefix, field) {
+        return `${prefix}_${this.toValidRustName(field.getParent().getName())}_${this.toValidRustName(field.getName())}`;
+    }
+
+    /**
+     * Returns the path of a generated function dedicated to a field, which
+     * is qualified when the field is inherited from another namespace.
+     * @param {string} prefix - the function prefix
+     * @param {Field} field - the field
+     * @param {Object} parameters - the parameter
+     * @return {string} the function path
+     * @private
+     */
+    toFieldFunctionPath(prefix, field, parameters) {
+        const name = this.toFieldFunctionName(prefix, field);
+        const namespace = field.getParent().getNamespace();
+        if (!parameters?.namespace || namespace === parameters.namespace) {
+            return name;
+        }
+        return `${this.toRustModulePath(namespace, parameters)}::${name}`;
+    }
+
+    /**
+     * Converts a JavaScript regular expression to a Rust regex pattern,
+     * translating the supported flags to inline flags. The regex crate has
+     * no look-around assertions or backreferences, so patterns using them
+     * are rejected rather than generating a validator that never matches.
+     * @param {RegExp} regex - the regular expression
+     * @param {string} path - the scalar or field validated by the regex
+     * @return {string} the Rust regex pattern
+     * @private
+     */
+    toRustRegex(regex, path) {
+        const source = regex.source;
+        let inClass = false;
+        for (let i = 0; i < source.length; i++) {
+            let unsupported = null;
+            if (source[i] === '\\') {
+                if (/^\\([1-9]|k<)/.test(source.slice(i))) {
+                    unsupported = 'backreferences';
+                }
+                i++;
+            } else if (inClass) {
+                inClass = source[i] !== ']';
+            } else if (source[i] === '[') {
+                inClass = true;
+            } else if (/^\(\?(=|!|<=|<!)/.test(source.slice(i))) {
+                unsupported = 'look-around assertions';
+            }
+            if (unsupported) {
+                throw new Error(`Unsupported regex ${regex} for ${path}: the Rust regex crate does not support ${unsupported}.`);
+            }
+        }
+        const flags = Array.from(regex.flags ?? '').filter(flag => ['i', 'm', 's'].includes(flag)).join('');
+        return flags ? `(?${flags})${source}` : source;
+    }
+
+    /**
+     * Converts a string to a Rust raw string literal.
+     * @param {string} value - the string
+     * @return {string} the Rust raw string literal
+     * @private
+     */
+    toRustRawString(value) {
+        let hashes = '#';
+        while (value.includes(`"${hashes}`)) {
+            hashes += '#';
+        }
+        return `r${hashes}"${value}"${hashes}`;
+    }
+
+    /**
+     * Converts a number to a Rust literal of the given numeric type.
+     * @param {number} value - the number
+     * @param {string} rustType - the Rust numeric type
+     * @return {string} the Rust literal
+     * @private
+     */
+    toRustNumber(value, rustType) {
+        const literal = `${value}`;
+        if (/^f(32|64)$/.test(rustType) && /^-?\d+$/.test(literal)) {
+            return `${literal}.0`;
+        }
+        return literal;
+    }
+
+    /**
+     * Writes the utils file shared by the generated modules.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    addUtilsModelFile(parameters) {
+        parameters.fileWriter.openFile(this.toSourceFilePath('utils.rs', parameters));
+        parameters.fileWriter.writeLine(0, this.getTimeLibrary(parameters).utilsImports);
+        parameters.fileWriter.writeLine(0, 'use serde::{ Deserialize, Serialize, Deserializer, Serializer };');
         parameters.fileWriter.writeLine(1, '');
-        parameters.fileWriter.writeLine(0, 'pub fn serialize_datetime<S>(datetime: &chrono::DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>')
-        parameters.fileWriter.writeLine(0, 'where')
-        parameters.fileWriter.writeLine(1, 'S: Serializer,')
-        parameters.fileWriter.writeLine(0, '{')
-        parameters.fileWriter.writeLine(1, 'let datetime_str = datetime.format("%+").to_string();')
-        parameters.fileWriter.writeLine(1, 'serializer.serialize_str(&datetime_str)')
-        parameters.fileWriter.writeLine(0, '}')
-        parameters.fileWriter.closeFile()
+        this.addDateTimeUtils(parameters);
+        this.addClassUtils(parameters);
+        this.addValidationUtils(parameters);
+        this.addEnumUtils(parameters);
+        this.addRelationshipUtils(parameters);
+        this.addIdentifiableUtils(parameters);
+        parameters.fileWriter.closeFile();
+    }
+
+    /**
+     * Adds the serde helpers of the DateTime fields to the utils file. There
+     * is a pair of functions for required, optional, array and optional array
+     * fields, and optional fields serialize None as null and deserialize null
+     * as None.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    addDateTimeUtils(parameters) {
+        const { type } = this.getTimeLibrary(parameters);
+        this.addDateTimeFunctions(parameters);
+        parameters.fileWriter.writeLine(0, `pub fn serialize_datetime<S>(datetime: &${type}, serializer: S) -> std::result::Result<S::Ok, S::Error>`);
+        parameters.fileWriter.writeLine(0, 'where');
+        parameters.fileWriter.writeLine(1, 'S: Serializer,');
+        parameters.fileWriter.writeLine(0, '{');
+        parameters.fileWriter.writeLine(1, 'serializer.serialize_str(&format_datetime(datetime))');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, `pub fn deserialize_datetime<'de, D>(deserializer: D) -> std::result::Result<${type}, D::Error>`);
+        parameters.fileWriter.writeLine(0, 'where');
+        parameters.fileWriter.writeLine(1, 'D: Deserializer<\'de>,');
+        parameters.fileWriter.writeLine(0, '{');
+        parameters.fileWriter.writeLine(1, 'let value = String::deserialize(deserializer)?;');
+        parameters.fileWriter.writeLine(1, 'parse_datetime(&value).map_err(serde::de::Error::custom)');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, `pub fn serialize_datetime_option<S>(datetime: &Option<${type}>, serializer: S) -> std::result::Result<S::Ok, S::Error>`);
+        parameters.fileWriter.writeLine(0, 'where');
+        parameters.fileWriter.writeLine(1, 'S: Serializer,');
+        parameters.fileWriter.writeLine(0, '{');
+        parameters.fileWriter.writeLine(1, 'match datetime {');
+        parameters.fileWriter.writeLine(2, 'Some(datetime) => serialize_datetime(datetime, serializer),');
+        parameters.fileWriter.writeLine(2, 'None => serializer.serialize_none(),');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, `pub fn deserialize_datetime_option<'de, D>(deserializer: D) -> std::result::Result<Option<${type}>, D::Error>`);
+        parameters.fileWriter.writeLine(0, 'where');
+        parameters.fileWriter.writeLine(1, 'D: Deserializer<\'de>,');
+        parameters.fileWriter.writeLine(0, '{');
+        parameters.fileWriter.writeLine(1, 'match Option::<String>::deserialize(deserializer)? {');
+        parameters.fileWriter.writeLine(2, 'Some(value) => parse_datetime(&value).map(Some).map_err(serde::de::Error::custom),');
+        parameters.fileWriter.writeLine(2, 'None => Ok(None),');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, `pub fn serialize_datetime_vec<S>(datetimes: &[${type}], serializer: S) -> std::result::Result<S::Ok, S::Error>`);
+        parameters.fileWriter.writeLine(0, 'where');
+        parameters.fileWriter.writeLine(1, 'S: Serializer,');
+        parameters.fileWriter.writeLine(0, '{');
+        parameters.fileWriter.writeLine(1, 'serializer.collect_seq(datetimes.iter().map(format_datetime))');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, `pub fn deserialize_datetime_vec<'de, D>(deserializer: D) -> std::result::Result<Vec<${type}>, D::Error>`);
+        parameters.fileWriter.writeLine(0, 'where');
+        parameters.fileWriter.writeLine(1, 'D: Deserializer<\'de>,');
+        parameters.fileWriter.writeLine(0, '{');
+        parameters.fileWriter.writeLine(1, 'Vec::<String>::deserialize(deserializer)?');
+        parameters.fileWriter.writeLine(2, '.iter()');
+        parameters.fileWriter.writeLine(2, '.map(|value| parse_datetime(value).map_err(serde::de::Error::custom))');
+        parameters.fileWriter.writeLine(2, '.collect()');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, `pub fn serialize_datetime_vec_option<S>(datetimes: &Option<Vec<${type}>>, serializer: S) -> std::result::Result<S::Ok, S::Error>`);
+        parameters.fileWriter.writeLine(0, 'where');
+        parameters.fileWriter.writeLine(1, 'S: Serializer,');
+        parameters.fileWriter.writeLine(0, '{');
+        parameters.fileWriter.writeLine(1, 'match datetimes {');
+        parameters.fileWriter.writeLine(2, 'Some(datetimes) => serialize_datetime_vec(datetimes, serializer),');
+        parameters.fileWriter.writeLine(2, 'None => serializer.serialize_none(),');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, `pub fn deserialize_datetime_vec_option<'de, D>(deserializer: D) -> std::result::Result<Option<Vec<${type}>>, D::Error>`);
+        parameters.fileWriter.writeLine(0, 'where');
+        parameters.fileWriter.writeLine(1, 'D: Deserializer<\'de>,');
+        parameters.fileWriter.writeLine(0, '{');
+        parameters.fileWriter.writeLine(1, 'match Option::<Vec<String>>::deserialize(deserializer)? {');
+        parameters.fileWriter.writeLine(2, 'Some(values) => values');
+        parameters.fileWriter.writeLine(3, '.iter()');
+        parameters.fileWriter.writeLine(3, '.map(|value| parse_datetime(value).map_err(serde::de::Error::custom))');
+        parameters.fileWriter.writeLine(3, '.collect::<std::result::Result<_, _>>()');
+        parameters.fileWriter.writeLine(3, '.map(Some),');
+        parameters.fileWriter.writeLine(2, 'None => Ok(None),');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '/// The default $timestamp of a transaction or an event: the current time.');
+        parameters.fileWriter.writeLine(0, `pub fn default_timestamp() -> ${type} {`);
+        switch (this.getTimeLibrary(parameters)) {
+        case timeLibraries['chrono-fixed-offset']:
+            parameters.fileWriter.writeLine(1, 'Utc::now().fixed_offset()');
+            break;
+        case timeLibraries.time:
+            parameters.fileWriter.writeLine(1, 'OffsetDateTime::now_utc()');
+            break;
+        default:
+            parameters.fileWriter.writeLine(1, 'Utc::now()');
+        }
+        parameters.fileWriter.writeLine(0, '}');
+    }
+
+    /**
+     * Adds the functions that parse and format a Concerto DateTime with the
+     * time library to the utils file.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    addDateTimeFunctions(parameters) {
+        switch (this.getTimeLibrary(parameters)) {
+        case timeLibraries['chrono-fixed-offset']:
+            parameters.fileWriter.writeLine(0, '/// Parses a Concerto DateTime: an RFC 3339 date and time, with an optional');
+            parameters.fileWriter.writeLine(0, '/// fraction of a second, and an offset that defaults to UTC as in Concerto.');
+            parameters.fileWriter.writeLine(0, 'pub fn parse_datetime(value: &str) -> std::result::Result<DateTime<FixedOffset>, chrono::ParseError> {');
+            parameters.fileWriter.writeLine(1, 'DateTime::parse_from_rfc3339(value).or_else(|error| {');
+            parameters.fileWriter.writeLine(2, 'NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")');
+            parameters.fileWriter.writeLine(3, '.map(|datetime| datetime.and_utc().fixed_offset())');
+            parameters.fileWriter.writeLine(3, '.map_err(|_| error)');
+            parameters.fileWriter.writeLine(1, '})');
+            parameters.fileWriter.writeLine(0, '}');
+            parameters.fileWriter.writeLine(0, '');
+            parameters.fileWriter.writeLine(0, '/// Formats a Concerto DateTime with milliseconds and its offset,');
+            parameters.fileWriter.writeLine(0, '/// e.g. 2024-01-02T05:04:05.678+02:00.');
+            parameters.fileWriter.writeLine(0, 'pub fn format_datetime(datetime: &DateTime<FixedOffset>) -> String {');
+            parameters.fileWriter.writeLine(1, 'datetime.to_rfc3339_opts(SecondsFormat::Millis, true)');
+            parameters.fileWriter.writeLine(0, '}');
+            break;
+        case timeLibraries.time:
+            parameters.fileWriter.writeLine(0, '/// Parses a Concerto DateTime: an RFC 3339 date and time, with an optional');
+            parameters.fileWriter.writeLine(0, '/// fraction of a second, and an offset that defaults to UTC as in Concerto.');
+            parameters.fileWriter.writeLine(0, 'pub fn parse_datetime(value: &str) -> std::result::Result<OffsetDateTime, time::error::Parse> {');
+            parameters.fileWriter.writeLine(1, 'OffsetDateTime::parse(value, &Rfc3339)');
+            parameters.fileWriter.writeLine(2, '.or_else(|error| OffsetDateTime::parse(&format!("{value}Z"), &Rfc3339).map_err(|_| error))');
+            parameters.fileWriter.writeLine(2, '.map(|datetime| datetime.to_offset(UtcOffset::UTC))');
+            parameters.fileWriter.writeLine(0, '}');
+            parameters.fileWriter.writeLine(0, '');
+            parameters.fileWriter.writeLine(0, '/// Formats a Concerto DateTime like concerto-core, in UTC with milliseconds,');
+            parameters.fileWriter.writeLine(0, '/// e.g. 2024-01-02T03:04:05.678Z.');
+            parameters.fileWriter.writeLine(0, 'pub fn format_datetime(datetime: &OffsetDateTime) -> String {');
+            parameters.fileWriter.writeLine(1, 'let datetime = datetime.to_offset(UtcOffset::UTC);');
+            parameters.fileWriter.writeLine(1, 'format!(');
+            parameters.fileWriter.writeLine(2, '"{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",');
+            parameters.fileWriter.writeLine(2, 'datetime.year(),');
+            parameters.fileWriter.writeLine(2, 'u8::from(datetime.month()),');
+            parameters.fileWriter.writeLine(2, 'datetime.day(),');
+            parameters.fileWriter.writeLine(2, 'datetime.hour(),');
+            parameters.fileWriter.writeLine(2, 'datetime.minute(),');
+            parameters.fileWriter.writeLine(2, 'datetime.second(),');
+            parameters.fileWriter.writeLine(2, 'datetime.millisecond(),');
+            parameters.fileWriter.writeLine(1, ')');
+            parameters.fileWriter.writeLine(0, '}');
+            break;
+        default:
+            parameters.fileWriter.writeLine(0, '/// Parses a Concerto DateTime: an RFC 3339 date and time, with an optional');
+            parameters.fileWriter.writeLine(0, '/// fraction of a second, and an offset that defaults to UTC as in Concerto.');
+            parameters.fileWriter.writeLine(0, 'pub fn parse_datetime(value: &str) -> std::result::Result<DateTime<Utc>, chrono::ParseError> {');
+            parameters.fileWriter.writeLine(1, 'match DateTime::parse_from_rfc3339(value) {');
+            parameters.fileWriter.writeLine(2, 'Ok(datetime) => Ok(datetime.with_timezone(&Utc)),');
+            parameters.fileWriter.writeLine(2, 'Err(error) => NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")');
+            parameters.fileWriter.writeLine(3, '.map(|datetime| datetime.and_utc())');
+            parameters.fileWriter.writeLine(3, '.map_err(|_| error),');
+            parameters.fileWriter.writeLine(1, '}');
+            parameters.fileWriter.writeLine(0, '}');
+            parameters.fileWriter.writeLine(0, '');
+            parameters.fileWriter.writeLine(0, '/// Formats a Concerto DateTime like concerto-core, in UTC with milliseconds,');
+            parameters.fileWriter.writeLine(0, '/// e.g. 2024-01-02T03:04:05.678Z.');
+            parameters.fileWriter.writeLine(0, 'pub fn format_datetime(datetime: &DateTime<Utc>) -> String {');
+            parameters.fileWriter.writeLine(1, 'datetime.to_rfc3339_opts(SecondsFormat::Millis, true)');
+            parameters.fileWriter.writeLine(0, '}');
+        }
+        parameters.fileWriter.writeLine(0, '');
+    }
+
+    /**
+     * Adds the ValidationError type and the functions that check the Concerto
+     * regex, length and range validators to the utils file.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    addValidationUtils(parameters) {
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '/// The error returned when a value violates a Concerto validator.');
+        parameters.fileWriter.writeLine(0, '#[derive(Debug, Clone, PartialEq, Eq)]');
+        parameters.fileWriter.writeLine(0, 'pub struct ValidationError {');
+        parameters.fileWriter.writeLine(1, '/// The scalar or field that failed validation, e.g. `Person.email`.');
+        parameters.fileWriter.writeLine(1, 'pub path: String,');
+        parameters.fileWriter.writeLine(1, 'pub message: String,');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl ValidationError {');
+        parameters.fileWriter.writeLine(1, 'pub fn new(path: &str, message: String) -> Self {');
+        parameters.fileWriter.writeLine(2, 'ValidationError { path: path.to_owned(), message }');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl std::fmt::Display for ValidationError {');
+        parameters.fileWriter.writeLine(1, 'fn fmt(&self, f: &mut std::fmt::Formatter<\'_>) -> std::fmt::Result {');
+        parameters.fileWriter.writeLine(2, 'write!(f, "{}: {}", self.path, self.message)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl std::error::Error for ValidationError {}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '/// Checks a value against a regex, compiled on first use into the given cell.');
+        parameters.fileWriter.writeLine(0, 'pub fn validate_regex(');
+        parameters.fileWriter.writeLine(1, 'path: &str,');
+        parameters.fileWriter.writeLine(1, 'value: &str,');
+        parameters.fileWriter.writeLine(1, 'regex: &std::sync::OnceLock<std::result::Result<regex::Regex, regex::Error>>,');
+        parameters.fileWriter.writeLine(1, 'pattern: &str,');
+        parameters.fileWriter.writeLine(0, ') -> std::result::Result<(), ValidationError> {');
+        parameters.fileWriter.writeLine(1, 'let regex = regex');
+        parameters.fileWriter.writeLine(2, '.get_or_init(|| regex::Regex::new(pattern))');
+        parameters.fileWriter.writeLine(2, '.as_ref()');
+        parameters.fileWriter.writeLine(2, '.map_err(|error| ValidationError::new(path, format!("invalid regex {}: {}", pattern, error)))?;');
+        parameters.fileWriter.writeLine(1, 'if regex.is_match(value) {');
+        parameters.fileWriter.writeLine(2, 'Ok(())');
+        parameters.fileWriter.writeLine(1, '} else {');
+        parameters.fileWriter.writeLine(2, 'Err(ValidationError::new(path, format!("value {:?} does not match regex {}", value, pattern)))');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'pub fn validate_length(path: &str, value: &str, min: Option<usize>, max: Option<usize>) -> std::result::Result<(), ValidationError> {');
+        parameters.fileWriter.writeLine(1, 'let length = value.chars().count();');
+        parameters.fileWriter.writeLine(1, 'if min.is_some_and(|min| length < min) || max.is_some_and(|max| length > max) {');
+        parameters.fileWriter.writeLine(2, 'return Err(ValidationError::new(path, format!("length {} is outside the bounds {:?}..{:?}", length, min, max)));');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(1, 'Ok(())');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'pub fn validate_range<T>(path: &str, value: T, lower: Option<T>, upper: Option<T>) -> std::result::Result<(), ValidationError>');
+        parameters.fileWriter.writeLine(0, 'where');
+        parameters.fileWriter.writeLine(1, 'T: PartialOrd + std::fmt::Debug,');
+        parameters.fileWriter.writeLine(0, '{');
+        parameters.fileWriter.writeLine(1, 'if lower.as_ref().is_some_and(|lower| value < *lower) || upper.as_ref().is_some_and(|upper| value > *upper) {');
+        parameters.fileWriter.writeLine(2, 'return Err(ValidationError::new(path, format!("value {:?} is outside the range {:?}..{:?}", value, lower, upper)));');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(1, 'Ok(())');
+        parameters.fileWriter.writeLine(0, '}');
+    }
+
+    /**
+     * Adds the ConcertoClass trait and the Class type of the $class field to
+     * the utils file. A Class always serializes to the fully qualified name
+     * of its type, and fails to deserialize from any other name.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    addClassUtils(parameters) {
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '/// Implemented by the structs of the Concerto classes and the unions of their hierarchies.');
+        parameters.fileWriter.writeLine(0, 'pub trait ConcertoClass {');
+        parameters.fileWriter.writeLine(1, '/// The fully qualified name of the Concerto type.');
+        parameters.fileWriter.writeLine(1, 'const CLASS: &\'static str;');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, '/// The fully qualified names of the classes of its instances: the variants of a union.');
+        parameters.fileWriter.writeLine(1, 'const CLASSES: &\'static [&\'static str] = &[Self::CLASS];');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '/// The $class of a Concerto class, which is always the fully qualified name of the type `T`.');
+        parameters.fileWriter.writeLine(0, 'pub struct Class<T>(std::marker::PhantomData<T>);');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> Default for Class<T> {');
+        parameters.fileWriter.writeLine(1, 'fn default() -> Self {');
+        parameters.fileWriter.writeLine(2, 'Class(std::marker::PhantomData)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> Clone for Class<T> {');
+        parameters.fileWriter.writeLine(1, 'fn clone(&self) -> Self {');
+        parameters.fileWriter.writeLine(2, '*self');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> Copy for Class<T> {}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> PartialEq for Class<T> {');
+        parameters.fileWriter.writeLine(1, 'fn eq(&self, _other: &Self) -> bool {');
+        parameters.fileWriter.writeLine(2, 'true');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> Eq for Class<T> {}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> PartialOrd for Class<T> {');
+        parameters.fileWriter.writeLine(1, 'fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {');
+        parameters.fileWriter.writeLine(2, 'Some(self.cmp(other))');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> Ord for Class<T> {');
+        parameters.fileWriter.writeLine(1, 'fn cmp(&self, _other: &Self) -> std::cmp::Ordering {');
+        parameters.fileWriter.writeLine(2, 'std::cmp::Ordering::Equal');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> std::hash::Hash for Class<T> {');
+        parameters.fileWriter.writeLine(1, 'fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T: ConcertoClass> std::fmt::Debug for Class<T> {');
+        parameters.fileWriter.writeLine(1, 'fn fmt(&self, f: &mut std::fmt::Formatter<\'_>) -> std::fmt::Result {');
+        parameters.fileWriter.writeLine(2, 'write!(f, "{:?}", T::CLASS)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T: ConcertoClass> Serialize for Class<T> {');
+        parameters.fileWriter.writeLine(1, 'fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>');
+        parameters.fileWriter.writeLine(1, 'where');
+        parameters.fileWriter.writeLine(2, 'S: Serializer,');
+        parameters.fileWriter.writeLine(1, '{');
+        parameters.fileWriter.writeLine(2, 'serializer.serialize_str(T::CLASS)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<\'de, T: ConcertoClass> Deserialize<\'de> for Class<T> {');
+        parameters.fileWriter.writeLine(1, 'fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>');
+        parameters.fileWriter.writeLine(1, 'where');
+        parameters.fileWriter.writeLine(2, 'D: Deserializer<\'de>,');
+        parameters.fileWriter.writeLine(1, '{');
+        parameters.fileWriter.writeLine(2, 'let class = String::deserialize(deserializer)?;');
+        parameters.fileWriter.writeLine(2, 'if class != T::CLASS {');
+        parameters.fileWriter.writeLine(3, 'return Err(serde::de::Error::custom(format!(');
+        parameters.fileWriter.writeLine(4, '"invalid $class: expected {}, found {}",');
+        parameters.fileWriter.writeLine(4, 'T::CLASS, class');
+        parameters.fileWriter.writeLine(3, ')));');
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(2, 'Ok(Class::default())');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+    }
+
+    /**
+     * Adds the Identifiable trait, implemented by identified assets,
+     * participants and other identified classes, to the utils file.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    addIdentifiableUtils(parameters) {
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '/// Implemented by the Concerto types that are identified by a field.');
+        parameters.fileWriter.writeLine(0, 'pub trait Identifiable {');
+        parameters.fileWriter.writeLine(1, '/// Returns the fully qualified name of the Concerto type of the instance.');
+        parameters.fileWriter.writeLine(1, 'fn fully_qualified_type_name(&self) -> &\'static str;');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, '/// Returns the value of the identifier field of the instance.');
+        parameters.fileWriter.writeLine(1, 'fn identifier(&self) -> &str;');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, '/// Returns a relationship to the instance.');
+        parameters.fileWriter.writeLine(1, 'fn to_relationship(&self) -> Relationship<Self>');
+        parameters.fileWriter.writeLine(1, 'where');
+        parameters.fileWriter.writeLine(2, 'Self: Sized,');
+        parameters.fileWriter.writeLine(1, '{');
+        parameters.fileWriter.writeLine(2, 'let fqn = self.fully_qualified_type_name();');
+        parameters.fileWriter.writeLine(2, 'let (namespace, type_name) = fqn.rsplit_once(\'.\').unwrap_or(("", fqn));');
+        parameters.fileWriter.writeLine(2, 'Relationship::new(namespace, type_name, self.identifier())');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+    }
+
+    /**
+     * Adds the EnumError type, returned when an enum is parsed from a string
+     * that is not one of its values, to the utils file.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    addEnumUtils(parameters) {
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '/// The error returned when a string is not a value of a Concerto enum.');
+        parameters.fileWriter.writeLine(0, '#[derive(Debug, Clone, PartialEq, Eq)]');
+        parameters.fileWriter.writeLine(0, 'pub struct EnumError {');
+        parameters.fileWriter.writeLine(1, '/// The enum, e.g. `State`.');
+        parameters.fileWriter.writeLine(1, 'pub name: String,');
+        parameters.fileWriter.writeLine(1, 'pub value: String,');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl EnumError {');
+        parameters.fileWriter.writeLine(1, 'pub fn new(name: &str, value: &str) -> Self {');
+        parameters.fileWriter.writeLine(2, 'EnumError { name: name.to_owned(), value: value.to_owned() }');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl std::fmt::Display for EnumError {');
+        parameters.fileWriter.writeLine(1, 'fn fmt(&self, f: &mut std::fmt::Formatter<\'_>) -> std::fmt::Result {');
+        parameters.fileWriter.writeLine(2, 'write!(f, "invalid value {} of enum {}", self.value, self.name)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl std::error::Error for EnumError {}');
+    }
+
+    /**
+     * Adds the generic Relationship type to the utils file. A relationship
+     * holds the resource URI of its target rather than embedding it, and keeps
+     * the target type as a marker for compile-time safety. A URI is only
+     * parsed if it refers to one of the classes of the target type.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    addRelationshipUtils(parameters) {
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '/// The error returned when a string is not a valid Concerto relationship URI.');
+        parameters.fileWriter.writeLine(0, '#[derive(Debug, Clone, PartialEq, Eq)]');
+        parameters.fileWriter.writeLine(0, 'pub struct RelationshipError {');
+        parameters.fileWriter.writeLine(1, 'pub uri: String,');
+        parameters.fileWriter.writeLine(1, 'pub message: String,');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl std::fmt::Display for RelationshipError {');
+        parameters.fileWriter.writeLine(1, 'fn fmt(&self, f: &mut std::fmt::Formatter<\'_>) -> std::fmt::Result {');
+        parameters.fileWriter.writeLine(2, 'write!(f, "invalid relationship {}: {}", self.uri, self.message)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl std::error::Error for RelationshipError {}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '/// A relationship to a Concerto resource of type `T`, serialized as a');
+        parameters.fileWriter.writeLine(0, '/// resource URI such as `resource:org.acme@1.0.0.Person#bob@acme.org`.');
+        parameters.fileWriter.writeLine(0, 'pub struct Relationship<T> {');
+        parameters.fileWriter.writeLine(1, '/// The namespace of the resource, e.g. `org.acme@1.0.0`.');
+        parameters.fileWriter.writeLine(1, 'pub namespace: String,');
+        parameters.fileWriter.writeLine(1, '/// The name of the type of the resource, e.g. `Person`.');
+        parameters.fileWriter.writeLine(1, 'pub type_name: String,');
+        parameters.fileWriter.writeLine(1, '/// The identifier of the resource.');
+        parameters.fileWriter.writeLine(1, 'pub id: String,');
+        parameters.fileWriter.writeLine(1, 'target: std::marker::PhantomData<fn() -> T>,');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> Relationship<T> {');
+        parameters.fileWriter.writeLine(1, 'pub fn new(namespace: &str, type_name: &str, id: &str) -> Self {');
+        parameters.fileWriter.writeLine(2, 'Relationship {');
+        parameters.fileWriter.writeLine(3, 'namespace: namespace.to_owned(),');
+        parameters.fileWriter.writeLine(3, 'type_name: type_name.to_owned(),');
+        parameters.fileWriter.writeLine(3, 'id: id.to_owned(),');
+        parameters.fileWriter.writeLine(3, 'target: std::marker::PhantomData,');
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, '/// Returns the fully qualified name of the type of the resource.');
+        parameters.fileWriter.writeLine(1, 'pub fn fully_qualified_type_name(&self) -> String {');
+        parameters.fileWriter.writeLine(2, 'format!("{}.{}", self.namespace, self.type_name)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T: ConcertoClass> Relationship<T> {');
+        parameters.fileWriter.writeLine(1, '/// Parses a resource URI such as `resource:org.acme@1.0.0.Person#bob@acme.org`,');
+        parameters.fileWriter.writeLine(1, '/// whose type must be one of the classes of `T`.');
+        parameters.fileWriter.writeLine(1, 'pub fn parse(uri: &str) -> std::result::Result<Self, RelationshipError> {');
+        parameters.fileWriter.writeLine(2, 'let error = |message: &str| RelationshipError { uri: uri.to_owned(), message: message.to_owned() };');
+        parameters.fileWriter.writeLine(2, 'let rest = uri.strip_prefix("resource:").ok_or_else(|| error("expected the resource: scheme"))?;');
+        parameters.fileWriter.writeLine(2, 'let (fqn, id) = rest.split_once(\'#\').ok_or_else(|| error("expected # before the identifier"))?;');
+        parameters.fileWriter.writeLine(2, 'let (namespace, type_name) = fqn.rsplit_once(\'.\').ok_or_else(|| error("expected a fully qualified type name"))?;');
+        parameters.fileWriter.writeLine(2, 'if namespace.is_empty() || type_name.is_empty() || id.is_empty() {');
+        parameters.fileWriter.writeLine(3, 'return Err(error("expected a namespace, a type name and an identifier"));');
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(2, 'if !T::CLASSES.contains(&fqn) {');
+        parameters.fileWriter.writeLine(3, 'return Err(error(&format!("expected a resource of type {}", T::CLASSES.join(" or "))));');
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(2, 'let id = decode_uri(id).ok_or_else(|| error("invalid percent encoding in the identifier"))?;');
+        parameters.fileWriter.writeLine(2, 'Ok(Relationship::new(namespace, type_name, &id))');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> std::fmt::Display for Relationship<T> {');
+        parameters.fileWriter.writeLine(1, 'fn fmt(&self, f: &mut std::fmt::Formatter<\'_>) -> std::fmt::Result {');
+        parameters.fileWriter.writeLine(2, 'write!(f, "resource:{}.{}#{}", self.namespace, self.type_name, encode_uri(&self.id))');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> std::fmt::Debug for Relationship<T> {');
+        parameters.fileWriter.writeLine(1, 'fn fmt(&self, f: &mut std::fmt::Formatter<\'_>) -> std::fmt::Result {');
+        parameters.fileWriter.writeLine(2, 'f.debug_tuple("Relationship").field(&self.to_string()).finish()');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T: ConcertoClass> std::str::FromStr for Relationship<T> {');
+        parameters.fileWriter.writeLine(1, 'type Err = RelationshipError;');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(1, 'fn from_str(uri: &str) -> std::result::Result<Self, Self::Err> {');
+        parameters.fileWriter.writeLine(2, 'Relationship::parse(uri)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> Clone for Relationship<T> {');
+        parameters.fileWriter.writeLine(1, 'fn clone(&self) -> Self {');
+        parameters.fileWriter.writeLine(2, 'Relationship::new(&self.namespace, &self.type_name, &self.id)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> PartialEq for Relationship<T> {');
+        parameters.fileWriter.writeLine(1, 'fn eq(&self, other: &Self) -> bool {');
+        parameters.fileWriter.writeLine(2, 'self.namespace == other.namespace && self.type_name == other.type_name && self.id == other.id');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> Eq for Relationship<T> {}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> PartialOrd for Relationship<T> {');
+        parameters.fileWriter.writeLine(1, 'fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {');
+        parameters.fileWriter.writeLine(2, 'Some(self.cmp(other))');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> Ord for Relationship<T> {');
+        parameters.fileWriter.writeLine(1, 'fn cmp(&self, other: &Self) -> std::cmp::Ordering {');
+        parameters.fileWriter.writeLine(2, '(&self.namespace, &self.type_name, &self.id).cmp(&(&other.namespace, &other.type_name, &other.id))');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> std::hash::Hash for Relationship<T> {');
+        parameters.fileWriter.writeLine(1, 'fn hash<H: std::hash::Hasher>(&self, state: &mut H) {');
+        parameters.fileWriter.writeLine(2, 'self.namespace.hash(state);');
+        parameters.fileWriter.writeLine(2, 'self.type_name.hash(state);');
+        parameters.fileWriter.writeLine(2, 'self.id.hash(state);');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<T> Serialize for Relationship<T> {');
+        parameters.fileWriter.writeLine(1, 'fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>');
+        parameters.fileWriter.writeLine(1, 'where');
+        parameters.fileWriter.writeLine(2, 'S: Serializer,');
+        parameters.fileWriter.writeLine(1, '{');
+        parameters.fileWriter.writeLine(2, 'serializer.serialize_str(&self.to_string())');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, 'impl<\'de, T: ConcertoClass> Deserialize<\'de> for Relationship<T> {');
+        parameters.fileWriter.writeLine(1, 'fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>');
+        parameters.fileWriter.writeLine(1, 'where');
+        parameters.fileWriter.writeLine(2, 'D: Deserializer<\'de>,');
+        parameters.fileWriter.writeLine(1, '{');
+        parameters.fileWriter.writeLine(2, 'let uri = String::deserialize(deserializer)?;');
+        parameters.fileWriter.writeLine(2, 'Relationship::parse(&uri).map_err(serde::de::Error::custom)');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '/// Percent-encodes an identifier like the JavaScript encodeURI function.');
+        parameters.fileWriter.writeLine(0, 'fn encode_uri(value: &str) -> String {');
+        parameters.fileWriter.writeLine(1, 'const UNESCAPED: &[u8] = b"-_.!~*\'();/?:@&=+$,#";');
+        parameters.fileWriter.writeLine(1, 'let mut encoded = String::with_capacity(value.len());');
+        parameters.fileWriter.writeLine(1, 'for byte in value.bytes() {');
+        parameters.fileWriter.writeLine(2, 'if byte.is_ascii_alphanumeric() || UNESCAPED.contains(&byte) {');
+        parameters.fileWriter.writeLine(3, 'encoded.push(char::from(byte));');
+        parameters.fileWriter.writeLine(2, '} else {');
+        parameters.fileWriter.writeLine(3, 'encoded.push_str(&format!("%{:02X}", byte));');
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(1, 'encoded');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+        parameters.fileWriter.writeLine(0, '/// Decodes a percent-encoded identifier, or returns None if it is malformed.');
+        parameters.fileWriter.writeLine(0, 'fn decode_uri(value: &str) -> Option<String> {');
+        parameters.fileWriter.writeLine(1, 'let bytes = value.as_bytes();');
+        parameters.fileWriter.writeLine(1, 'let mut decoded = Vec::with_capacity(bytes.len());');
+        parameters.fileWriter.writeLine(1, 'let mut index = 0;');
+        parameters.fileWriter.writeLine(1, 'while index < bytes.len() {');
+        parameters.fileWriter.writeLine(2, 'if bytes[index] == b\'%\' {');
+        parameters.fileWriter.writeLine(3, 'let hex = bytes.get(index + 1..index + 3)?;');
+        parameters.fileWriter.writeLine(3, 'if !hex.iter().all(u8::is_ascii_hexdigit) {');
+        parameters.fileWriter.writeLine(4, 'return None;');
+        parameters.fileWriter.writeLine(3, '}');
+        parameters.fileWriter.writeLine(3, 'decoded.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);');
+        parameters.fileWriter.writeLine(3, 'index += 3;');
+        parameters.fileWriter.writeLine(2, '} else {');
+        parameters.fileWriter.writeLine(3, 'decoded.push(bytes[index]);');
+        parameters.fileWriter.writeLine(3, 'index += 1;');
+        parameters.fileWriter.writeLine(2, '}');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(1, 'String::from_utf8(decoded).ok()');
+        parameters.fileWriter.writeLine(0, '}');
     }
 }
 
@@ -0,0 +1,1101 @@
+/*
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+'use strict';
+
+const chai = require('chai');
+chai.should();
+const { expect } = require('expect');
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+const { spawnSync } = require('child_process');
+const tmp = require('tmp-promise');
+
+const RustVisitor = require('../../../../lib/codegen/fromcto/rust/rustvisitor');
+const AbstractRustPlugin = require('../../../../lib/codegen/abstractrustplugin');
+const { Factory, ModelManager, Serializer } = require('@accordproject/concerto-core');
+const { InMemoryWriter } = require('@accordproject/concerto-util');
+
+/**
+ * A plugin adding a domain method to each struct and enum.
+ */
+class DescribePlugin extends AbstractRustPlugin {
+    /**
+     * Imports the Display trait, if the file has a describe method.
+     * @param {ModelFile} modelFile - the model file being visited
+     * @param {Object} parameters - the parameter
+     */
+    addFileImports(modelFile, parameters) {
+        if (modelFile.getAllDeclarations().some(declaration => declaration.isEnum() || (declaration.isClassDeclaration() && !declaration.isAbstract()))) {
+            parameters.fileWriter.writeLine(0, 'use std::fmt::Display;');
+        }
+    }
+
+    /**
+     * Adds a module describing the namespace.
+     * @param {ModelFile} modelFile - the model file being visited
+     * @param {Object} parameters - the parameter
+     */
+    addFileModules(modelFile, parameters) {
+        parameters.fileWriter.writeLine(0, 'pub mod description {');
+        parameters.fileWriter.writeLine(1, `pub const NAMESPACE: &str = "${modelFile.getNamespace()}";`);
+        parameters.fileWriter.writeLine(0, '}');
+    }
+
+    /**
+     * Adds nothing.
+     */
+    addClassImports() {
+    }
+
+    /**
+     * Adds a lint attribute.
+     * @param {ClassDeclaration} clazz - the clazz being visited
+     * @param {Object} parameters - the parameter
+     */
+    addClassAttributes(clazz, parameters) {
+        parameters.fileWriter.writeLine(0, '#[allow(clippy::struct_field_names)]');
+    }
+
+    /**
+     * Adds a describe method.
+     * @param {ClassDeclaration} clazz - the clazz being visited
+     * @param {Object} parameters - the parameter
+     */
+    addClassImpls(clazz, parameters) {
+        parameters.fileWriter.writeLine(0, `impl ${clazz.getName()} {`);
+        parameters.fileWriter.writeLine(1, 'pub fn describe(&self) -> impl Display {');
+        parameters.fileWriter.writeLine(2, 'Self::CLASS');
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+    }
+
+    /**
+     * Adds nothing.
+     */
+    addClassModules() {
+    }
+
+    /**
+     * Adds nothing.
+     */
+    addEnumImports() {
+    }
+
+    /**
+     * Adds nothing.
+     */
+    addEnumAttributes() {
+    }
+
+    /**
+     * Adds a describe method.
+     * @param {EnumDeclaration} enumDecl - the enum being visited
+     * @param {Object} parameters - the parameter
+     */
+    addEnumImpls(enumDecl, parameters) {
+        parameters.fileWriter.writeLine(0, `impl ${enumDecl.getName()} {`);
+        parameters.fileWriter.writeLine(1, 'pub fn describe(&self) -> impl Display {');
+        parameters.fileWriter.writeLine(2, `"${enumDecl.getFullyQualifiedName()}"`);
+        parameters.fileWriter.writeLine(1, '}');
+        parameters.fileWriter.writeLine(0, '}');
+        parameters.fileWriter.writeLine(0, '');
+    }
+
+    /**
+     * Adds a module of the enum.
+     * @param {EnumDeclaration} enumDecl - the enum being visited
+     * @param {Object} parameters - the parameter
+     */
+    addEnumModules(enumDecl, parameters) {
+        parameters.fileWriter.writeLine(0, `pub mod ${enumDecl.getName().toLowerCase()}_values {}`);
+        parameters.fileWriter.writeLine(0, '');
+    }
+}
+
+/**
+ * Generates the Rust code for CTO models.
+ * @param {string[]} models - the CTO models
+ * @param {Object} [options] - additional visitor parameters
+ * @param {AbstractRustPlugin} [plugin] - the plugin of the visitor
+ * @return {Map} the generated files, keyed by file name
+ */
+function generateModels(models, options, plugin) {
+    const modelManager = new ModelManager();
+    models.forEach((model, index) => modelManager.addCTOModel(model, `model${index}.cto`, true));
+    modelManager.validateModelFiles();
+    const writer = new InMemoryWriter();
+    const visitor = new RustVisitor();
+    if (plugin) {
+        visitor.plugin = plugin;
+    }
+    modelManager.accept(visitor, Object.assign({ fileWriter: writer }, options));
+    return writer.getFilesInMemory();
+}
+
+/**
+ * Generates the Rust code for a model file.
+ * @param {string} modelFile - the path of the CTO file
+ * @param {Object} [options] - additional visitor parameters
+ * @param {AbstractRustPlugin} [plugin] - the plugin of the visitor
+ * @return {Map} the generated files, keyed by file name
+ */
+function generate(modelFile, options, plugin) {
+    return generateModels([fs.readFileSync(modelFile, 'utf-8')], options, plugin);
+}
+
+/**
+ * The target directory shared by the generated crates, so that their
+ * dependencies are only compiled once.
+ */
+const CARGO_TARGET_DIR = process.env.CARGO_TARGET_DIR || path.join(os.tmpdir(), 'concerto-codegen-rust-target');
+
+/**
+ * Returns true if the tests running cargo are enabled, by setting the
+ * CONCERTO_CARGO_TESTS environment variable to true, and cargo can be run
+ * on this machine. The build workflow enables them on Linux.
+ * @return {boolean} true if cargo is available
+ */
+function hasCargo() {
+    if (process.env.CONCERTO_CARGO_TESTS !== 'true') {
+        return false;
+    }
+    const result = spawnSync('cargo', ['--version']);
+    return !result.error && result.status === 0;
+}
+
+/**
+ * Returns true if cargo clippy can be run on this machine.
+ * @return {boolean} true if clippy is available
+ */
+function hasClippy() {
+    const result = spawnSync('cargo', ['clippy', '--version']);
+    return !result.error && result.status === 0;
+}
+
+/**
+ * Writes a generated Cargo crate to disk and runs a cargo command on it,
+ * denying warnings so that the generated code stays warning free.
+ * @param {Map} files - the generated files of the crate, keyed by path
+ * @param {string} command - the cargo command
+ * @return {Promise<Object>} the result of the cargo process
+ */
+async function cargo(files, command) {
+    const { path: dir, cleanup } = await tmp.dir({ unsafeCleanup: true });
+    try {
+        files.forEach((value, key) => {
+            fs.mkdirSync(path.dirname(path.join(dir, key)), { recursive: true });
+            fs.writeFileSync(path.join(dir, key), value);
+        });
+        const env = Object.assign({}, process.env, { CARGO_TARGET_DIR, RUSTFLAGS: '-D warnings' });
+        return spawnSync('cargo', [command, '--quiet'], { cwd: dir, encoding: 'utf-8', env });
+    } finally {
+        await cleanup();
+    }
+}
+
+/**
+ * Writes a generated Cargo crate to disk and runs cargo check on it.
+ * @param {Map} files - the generated files of the crate, keyed by path
+ * @return {Promise<Object>} the result of the cargo process
+ */
+async function cargoCheck(files) {
+    return cargo(files, 'check');
+}
+
+/**
+ * Writes a generated Cargo crate to disk with integration tests, and runs cargo test on it.
+ * The tests read and write JSON with the serde_json dependency of the crate.
+ * @param {Map} files - the generated files of the crate, keyed by path
+ * @param {Object} tests - the Rust sources of the integration tests, keyed by file name
+ * @return {Promise<Object>} the result of the cargo process
+ */
+async function cargoTest(files, tests) {
+    Object.entries(tests).forEach(([name, source]) => files.set(`tests/${name}`, source));
+    return cargo(files, 'test');
+}
+
+const RECURSIVE_MODEL = `namespace org.acme.recursive@1.0.0
+
+concept Person {
+    o String name
+    o Person child optional
+    o Person[] children
+    o Pet pet optional
+}
+
+concept Pet {
+    o Person owner
+}
+
+abstract concept Shape {
+}
+
+concept Circle extends Shape {
+    o Node node optional
+}
+
+concept Node {
+    o Shape shape
+}
+
+concept Household {
+    o Person head
+}
+`;
+
+const COLLIDING_MODELS = [`namespace org.accordproject.runtime@0.2.0
+
+concept State {
+    o String stateId
+}
+
+abstract concept Request {
+    o String requestId regex=/[a-z]+/
+    o State state
+}
+`, `namespace org.other.runtime@1.0.0
+
+import org.accordproject.runtime@0.2.0.{Request}
+
+concept State {
+    o Integer counter
+}
+
+abstract concept OtherRequest extends Request {
+    o State otherState
+}
+`, `namespace org.acme.app@1.0.0
+
+import org.other.runtime@1.0.0.{OtherRequest}
+
+concept State {
+    o String name
+}
+
+concept MyRequest extends OtherRequest {
+    o State mine
+}
+`];
+
+const VERSIONED_MODELS = [`namespace org.acme.hr@1.0.0
+
+concept Person {
+    o String name
+}
+`, `namespace org.acme.hr@2.0.0
+
+import org.acme.hr@1.0.0.{Person}
+
+concept Employee {
+    o String name
+    o Integer age
+    o Person previous optional
+}
+`, `namespace org.acme@1.0.0
+
+import org.acme.hr@2.0.0.{Employee}
+
+concept Company {
+    o Employee[] employees
+}
+`];
+
+const EXTERNAL_MODEL = `namespace org.acme.helloworld@1.0.0
+
+import org.accordproject.runtime@0.2.0.{Request} from https://models.accordproject.org/accordproject/runtime@0.2.0.cto
+
+transaction MyRequest extends Request {
+    o String input
+}
+`;
+
+const DATETIME_MODEL = `namespace org.acme.time@1.0.0
+scalar Deadline extends DateTime
+concept Schedule {
+    o DateTime start
+    o DateTime end optional
+    o DateTime[] holidays
+    o DateTime[] closures optional
+    o Deadline deadline optional
+    o Deadline[] milestones optional
+}`;
+
+const DATETIME_DEFAULT_MODEL = `namespace org.acme.time@1.0.0
+concept Schedule {
+    o DateTime start default="2024-01-02T03:04:05.678+02:00"
+    o DateTime end optional
+}`;
+
+const DATETIME_TESTS = `use concerto_model::org_acme_time_1_0_0::Schedule;
+use concerto_model::utils::*;
+use serde_json::{ json, Value };
+
+fn round_trip(value: Value) -> Value {
+    let schedule: Schedule = serde_json::from_value(value).unwrap();
+    serde_json::to_value(&schedule).unwrap()
+}
+
+#[test]
+fn round_trips_required_optional_and_array_datetimes() {
+    let value = json!({
+        "$class": "org.acme.time@1.0.0.Schedule",
+        "start": "2024-01-02T03:04:05.678Z",
+        "end": "2024-02-03T04:05:06.789Z",
+        "holidays": ["2024-12-25T00:00:00.000Z", "2024-12-26T00:00:00.000Z"],
+        "closures": ["2024-08-01T00:00:00Z"],
+        "deadline": "2024-06-30T17:00:00+02:00",
+        "milestones": ["2024-03-01T00:00:00Z", "2024-04-01T00:00:00Z"]
+    });
+    let serialized = round_trip(value);
+    assert_eq!(serialized, round_trip(serialized.clone()));
+    assert_eq!(serialized["holidays"].as_array().unwrap().len(), 2);
+    assert_eq!(serialized["closures"][0], "2024-08-01T00:00:00.000Z");
+    assert_eq!(serialized["deadline"], "2024-06-30T15:00:00.000Z");
+    assert_eq!(serialized["milestones"][1], "2024-04-01T00:00:00.000Z");
+}
+
+#[test]
+fn deserializes_missing_and_null_optional_datetimes() {
+    for null in [None, Some(Value::Null)] {
+        let mut value = json!({
+            "$class": "org.acme.time@1.0.0.Schedule",
+            "start": "2024-01-02T03:04:05.678Z",
+            "holidays": []
+        });
+        if let Some(null) = null {
+            for field in ["end", "closures", "deadline", "milestones"] {
+                value[field] = null.clone();
+            }
+        }
+        let schedule: Schedule = serde_json::from_value(value).unwrap();
+        assert!(schedule.end.is_none());
+        assert!(schedule.closures.is_none());
+        assert!(schedule.deadline.is_none());
+        assert!(schedule.milestones.is_none());
+        let serialized = serde_json::to_value(&schedule).unwrap();
+        for field in ["end", "closures", "deadline", "milestones"] {
+            assert!(serialized.get(field).is_none(), "{}", field);
+        }
+    }
+}
+
+#[test]
+fn parses_the_datetime_formats_of_concerto() {
+    let expected = parse_datetime("2024-01-02T03:04:05.000Z").unwrap();
+    for value in ["2024-01-02T03:04:05Z", "2024-01-02T05:04:05+02:00", "2024-01-02T03:04:05.000+00:00", "2024-01-02T03:04:05"] {
+        assert_eq!(parse_datetime(value).unwrap(), expected, "{}", value);
+    }
+    assert!(parse_datetime("2024-01-02T03:04:05 UTC").is_err());
+}
+
+#[test]
+fn formats_datetimes_like_concerto() {
+    let datetime = parse_datetime("2024-01-02T05:04:05.6+02:00").unwrap();
+    assert_eq!(format_datetime(&datetime), "2024-01-02T03:04:05.600Z");
+}
+
+#[test]
+fn serializes_none_as_null() {
+    let value = serialize_datetime_option(&None, serde_json::value::Serializer).unwrap();
+    assert_eq!(value, Value::Null);
+    let value = serialize_datetime_vec_option(&None, serde_json::value::Serializer).unwrap();
+    assert_eq!(value, Value::Null);
+}
+`;
+
+const FIXED_OFFSET_TESTS = `use concerto_model::org_acme_time_1_0_0::Schedule;
+use concerto_model::utils::*;
+use serde_json::json;
+
+#[test]
+fn keeps_the_offset_of_datetimes() {
+    let value = json!({
+        "$class": "org.acme.time@1.0.0.Schedule",
+        "start": "2024-01-02T05:04:05.678+02:00",
+        "holidays": ["2024-12-25T00:00:00-05:00"],
+        "deadline": "2024-06-30T17:00:00Z"
+    });
+    let schedule: Schedule = serde_json::from_value(value).unwrap();
+    assert_eq!(schedule.start, parse_datetime("2024-01-02T03:04:05.678Z").unwrap());
+    assert_eq!(schedule.start.offset().local_minus_utc(), 7200);
+    let serialized = serde_json::to_value(&schedule).unwrap();
+    assert_eq!(serialized["start"], "2024-01-02T05:04:05.678+02:00");
+    assert_eq!(serialized["holidays"][0], "2024-12-25T00:00:00.000-05:00");
+    assert_eq!(serialized["deadline"], "2024-06-30T17:00:00.000Z");
+}
+`;
+
+const ENUM_MODEL = `namespace org.acme.status@1.0.0
+enum Status {
+    o PASSED_TESTING
+    o passedTesting
+    o self
+    o type
+    o _1
+    o Apple
+}
+concept Check {
+    o Status status default="PASSED_TESTING"
+    o Status[] history optional
+}`;
+
+const ENUM_TESTS = `use concerto_model::org_acme_status_1_0_0::{ Check, Status };
+use concerto_model::utils::*;
+use serde_json::json;
+
+#[test]
+fn serializes_the_names_of_the_enum_values() {
+    let value = json!({
+        "$class": "org.acme.status@1.0.0.Check",
+        "history": ["passedTesting", "self", "type", "_1", "Apple"]
+    });
+    let check: Check = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(check.status, Status::PassedTesting);
+    assert_eq!(check.history.as_deref(), Some(&[Status::PassedTesting_, Status::Self_, Status::Type, Status::V1, Status::Apple][..]));
+    let serialized = serde_json::to_value(&check).unwrap();
+    assert_eq!(serialized["status"], "PASSED_TESTING");
+    assert_eq!(serialized["history"], value["history"]);
+    assert!(serde_json::from_value::<Status>(json!("PassedTesting")).is_err());
+}
+
+#[test]
+fn converts_enum_values_to_and_from_strings() {
+    assert_eq!(Status::ALL.len(), 6);
+    for status in Status::ALL {
+        assert_eq!(status.as_str().parse::<Status>(), Ok(status));
+        assert_eq!(status.to_string(), status.as_str());
+    }
+    assert_eq!("PassedTesting".parse::<Status>(), Err(EnumError::new("Status", "PassedTesting")));
+}
+`;
+
+const VALIDATION_MODEL = `namespace org.acme.codes@1.0.0
+scalar Code extends String default="lowercase" regex=/^[A-Z]{3}$/
+concept Account {
+    o Code code
+    o Code[] previous optional
+    o String name regex=/^[a-z]+$/
+}
+abstract concept Owner {
+}
+concept Company extends Owner {
+    o Account account
+}
+concept Bank {
+    o Account main
+    o Account[] accounts optional
+    o Owner owner optional
+    o Bank parent optional
+}
+concept Release {
+    o String name
+    o String build optional regex=/^[0-9]+$/
+    o String with_build optional
+}`;
+
+const VALIDATION_TESTS = `use concerto_model::org_acme_codes_1_0_0::{ Account, Bank, Code, Company, OwnerUnion, Release };
+use serde_json::json;
+
+#[test]
+fn validates_scalars() {
+    let code = Code::new("ABC".to_owned()).unwrap();
+    assert_eq!(code.value(), "ABC");
+    assert_eq!(code.into_inner(), "ABC");
+    assert_eq!(Code::new("abc".to_owned()).unwrap_err().path, "Code");
+    assert!(serde_json::from_value::<Code>(json!("abc")).is_err());
+}
+
+#[test]
+fn validates_the_fields_and_scalars_of_structs() {
+    let value = json!({
+        "$class": "org.acme.codes@1.0.0.Account",
+        "code": "ABC",
+        "previous": ["DEF"],
+        "name": "bob"
+    });
+    let account: Account = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(account.validate(), Ok(()));
+    let mut invalid = value.clone();
+    invalid["previous"] = json!(["def"]);
+    assert!(serde_json::from_value::<Account>(invalid).is_err());
+
+    let mut account = Account::default();
+    assert_eq!(account.validate().unwrap_err().path, "Account.name");
+    account.name = "bob".to_owned();
+    assert_eq!(account.validate().unwrap_err().path, "Code");
+    account.code = Code::new("ABC".to_owned()).unwrap();
+    assert_eq!(account.validate(), Ok(()));
+}
+
+#[test]
+fn validates_the_structs_and_unions_of_fields() {
+    let valid = Account::new(Code::new("ABC".to_owned()).unwrap(), "bob".to_owned());
+    let invalid = Account::new(Code::new("ABC".to_owned()).unwrap(), "Bob".to_owned());
+    assert!(Bank::builder(valid.clone()).build().is_ok());
+    assert_eq!(Bank::builder(invalid.clone()).build().unwrap_err().path, "Account.name");
+    let bank = Bank::builder(valid.clone()).accounts(vec![valid.clone(), invalid.clone()]).build();
+    assert_eq!(bank.unwrap_err().path, "Account.name");
+    let bank = Bank::builder(valid.clone()).owner(OwnerUnion::Company(Company::new(invalid.clone()))).build();
+    assert_eq!(bank.unwrap_err().path, "Account.name");
+    let parent = Bank::new(invalid);
+    assert_eq!(Bank::builder(valid).parent(Box::new(parent)).build().unwrap_err().path, "Account.name");
+}
+
+#[test]
+fn builds_structs_with_a_build_field() {
+    let release = Release::builder("concerto".to_owned())
+        .with_with_build("42".to_owned())
+        .with_build("nightly".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(release.build.as_deref(), Some("42"));
+    assert_eq!(release.with_build.as_deref(), Some("nightly"));
+    let release = Release::builder("concerto".to_owned()).with_with_build("latest".to_owned()).build();
+    assert_eq!(release.unwrap_err().path, "Release.build");
+}
+`;
+
+const RELATIONSHIP_MODEL = `namespace org.acme.links@1.0.0
+abstract participant Person identified by email {
+    o String email
+}
+participant Employee extends Person {}
+participant Contractor extends Person {}
+asset Laptop identified by serial {
+    o String serial
+    --> Employee owner
+    --> Person[] users optional
+}`;
+
+const RELATIONSHIP_TESTS = `use concerto_model::org_acme_links_1_0_0::{ Employee, Laptop, PersonUnion };
+use concerto_model::utils::*;
+use serde_json::json;
+
+#[test]
+fn parses_relationships_to_the_target_type() {
+    let employee: Relationship<Employee> = "resource:org.acme.links@1.0.0.Employee#bob%40acme.org".parse().unwrap();
+    assert_eq!(employee.id, "bob@acme.org");
+    let person: Relationship<PersonUnion> = Relationship::parse("resource:org.acme.links@1.0.0.Contractor#eve").unwrap();
+    assert_eq!(person.fully_qualified_type_name(), "org.acme.links@1.0.0.Contractor");
+}
+
+#[test]
+fn rejects_relationships_to_other_types() {
+    let error = Relationship::<Employee>::parse("resource:org.acme.links@1.0.0.Contractor#eve").unwrap_err();
+    assert_eq!(error.message, "expected a resource of type org.acme.links@1.0.0.Employee");
+    assert!(Relationship::<Employee>::parse("resource:org.acme.other@1.0.0.Employee#eve").is_err());
+    assert!(Relationship::<PersonUnion>::parse("resource:org.acme.links@1.0.0.Person#eve").is_err());
+    assert!(Relationship::<PersonUnion>::parse("resource:org.acme.links@1.0.0.Laptop#eve").is_err());
+    let laptop = json!({
+        "$class": "org.acme.links@1.0.0.Laptop",
+        "serial": "L1",
+        "owner": "resource:org.acme.links@1.0.0.Contractor#eve"
+    });
+    assert!(serde_json::from_value::<Laptop>(laptop).is_err());
+}
+`;
+
+const SYSTEM_REFERENCE_MODEL = `namespace org.acme.holder@1.0.0
+participant Person identified by email {
+    o String email
+}
+concept Holder {
+    o Concept content
+    --> Participant owner
+}`;
+
+const SHADOWING_MODEL = `namespace org.acme.shadowing@1.0.0
+concept Class {
+    o String name regex=/^[a-z]+$/
+}
+concept Result {
+    o Class class
+}`;
+
+const DERIVES = {
+    struct: ['Clone', 'PartialEq', 'Eq', 'Hash', 'PartialOrd', 'Ord', 'Default'],
+    enum: ['Clone', 'Copy', 'PartialEq', 'Eq', 'Hash', 'PartialOrd', 'Ord'],
+    scalar: ['Clone', 'PartialEq', 'Eq', 'Hash', 'PartialOrd', 'Ord'],
+};
+
+// Derives configured differently for each kind, which the structs only derive when their fields implement them.
+const MIXED_DERIVES = [
+    { struct: ['Clone', 'PartialEq', 'Eq', 'Hash'], enum: ['Copy'] },
+    { struct: ['Clone', 'Copy', 'PartialEq', 'PartialOrd'], enum: ['Clone', 'Copy', 'PartialEq', 'PartialOrd'], scalar: ['Clone', 'PartialEq'] },
+];
+
+describe('RustVisitor compilation', function () {
+    const primitivesModel = './test/codegen/fromcto/data/model/primitives.cto';
+    const circularModel = './test/codegen/fromcto/data/model/circular.cto';
+    const hrModel = './test/codegen/fromcto/data/model/hr.cto';
+
+    it('should generate every Concerto primitive type', () => {
+        const files = generate(primitivesModel);
+        files.forEach((value, key) => {
+            expect({ value, key }).toMatchSnapshot();
+        });
+        const code = files.get('org_acme_primitives_1_0_0.rs');
+        code.should.contain('pub integer_value: i32,');
+        code.should.contain('pub long_value: i64,');
+        code.should.contain('pub double_value: f64,');
+        code.should.contain('pub boolean_value: bool,');
+        code.should.contain('pub string_value: String,');
+        code.should.contain('pub date_time_value: DateTime<Utc>,');
+    });
+
+    it('should use the primitive types from the parameters', () => {
+        const files = generate(primitivesModel, { primitiveTypes: { Integer: 'i64', Long: 'i128' } });
+        const code = files.get('org_acme_primitives_1_0_0.rs');
+        code.should.contain('pub integer_value: i64,');
+        code.should.contain('pub long_value: i128,');
+        code.should.contain('pub struct Count(pub i64);');
+    });
+
+    it('should box recursive fields', () => {
+        const code = generateModels([RECURSIVE_MODEL]).get('org_acme_recursive_1_0_0.rs');
+        code.should.contain('pub child: Option<Box<Person>>,');
+        code.should.contain('pub children: Vec<Person>,');
+        code.should.contain('pub pet: Option<Box<Pet>>,');
+        code.should.contain('pub owner: Box<Person>,');
+        code.should.contain('pub node: Option<Box<Node>>,');
+        code.should.contain('pub shape: Box<ShapeUnion>,');
+        code.should.contain('pub head: Person,');
+        code.should.contain('pub name: String,');
+    });
+
+    it('should box fields that are recursive across namespaces', () => {
+        const files = generateModels([
+            'namespace org.a@1.0.0\nimport org.b@1.0.0.{B}\nconcept A {\n    o B b optional\n}\n',
+            'namespace org.b@1.0.0\nimport org.a@1.0.0.{A}\nconcept B {\n    o A a\n}\n',
+        ]);
+        files.get('org_a_1_0_0.rs').should.contain('pub b: Option<Box<B>>,');
+        files.get('org_b_1_0_0.rs').should.contain('pub a: Box<A>,');
+    });
+
+    it('should reference the targets of relationships', () => {
+        const code = generate(hrModel).get('org_acme_hr_1_0_0.rs');
+        code.should.contain('pub manager: Option<Relationship<Manager>>,');
+        code.should.contain('pub reports: Option<Vec<Relationship<PersonUnion>>>,');
+        code.should.contain('pub employee: Relationship<EmployeeUnion>,');
+    });
+
+    it('should implement Identifiable for identified classes', () => {
+        const code = generate(hrModel).get('org_acme_hr_1_0_0.rs');
+        code.should.contain('impl Identifiable for Laptop {');
+        code.should.contain('impl Identifiable for Manager {');
+        code.should.contain('impl Identifiable for PersonUnion {');
+        code.should.contain('&self.serial_number');
+        code.should.contain('&self.email');
+        code.should.not.contain('impl Identifiable for Address {');
+    });
+
+    it('should handle the Concerto system properties', () => {
+        const files = generate(hrModel);
+        const code = files.get('org_acme_hr_1_0_0.rs');
+        code.should.contain('pub _class: crate::lib::utils::Class<Laptop>,');
+        code.should.contain('impl ConcertoClass for Laptop {\n   const CLASS: &\'static str = "org.acme.hr@1.0.0.Laptop";');
+        code.should.contain('fn _identifier(&self) -> &String {\n      &self.serial_number\n   }');
+        code.should.not.contain('pub _identifier: String,');
+        code.should.contain('rename = "$timestamp",');
+        code.should.contain('default = "default_timestamp",');
+        files.get('utils.rs').should.contain('"invalid $class: expected {}, found {}",');
+    });
+
+    it('should generate constructors and builders', () => {
+        const code = generate(hrModel).get('org_acme_hr_1_0_0.rs');
+        code.should.contain('pub fn new(make: LaptopMake, serial_number: String) -> Self {');
+        code.should.contain('pub fn builder(name: String, headquarters: Address) -> CompanyBuilder {');
+        code.should.contain('pub fn manager(mut self, manager: Relationship<Manager>) -> Self {');
+        code.should.contain('pub fn build(self) -> std::result::Result<Employee, ValidationError> {');
+        code.should.contain('_timestamp: default_timestamp(),');
+    });
+
+    it('should rename the builder setters that collide with the build function', () => {
+        const code = generateModels([VALIDATION_MODEL]).get('org_acme_codes_1_0_0.rs');
+        code.should.contain('pub fn with_with_build(mut self, build: String) -> Self {');
+        code.should.contain('pub fn with_build(mut self, with_build: String) -> Self {');
+        code.should.contain('pub fn build(self) -> std::result::Result<Release, ValidationError> {');
+    });
+
+    it('should honour the modelled defaults', () => {
+        const code = generate(hrModel).get('org_acme_hr_1_0_0.rs');
+        code.should.contain('impl Default for SSN {');
+        code.should.contain('default = "default_person_ssn",');
+        code.should.contain('#[derive(Debug, Serialize, Deserialize, Default)]\npub struct Company {');
+        code.should.not.contain('impl Default for Company {');
+        code.should.not.contain('impl Default for Employee {');
+    });
+
+    it('should write the configured derives and attributes', () => {
+        const code = generate(hrModel, { derives: DERIVES, attributes: { struct: ['#[non_exhaustive]'] } }).get('org_acme_hr_1_0_0.rs');
+        code.should.contain('#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]\n#[non_exhaustive]\npub struct Address {');
+        code.should.contain('#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd)]\n#[non_exhaustive]\npub struct Employee {');
+        code.should.contain('#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]\npub enum State {');
+    });
+
+    it('should only derive the configured traits that the fields implement', () => {
+        let code = generate(hrModel, { derives: MIXED_DERIVES[0] }).get('org_acme_hr_1_0_0.rs');
+        code.should.contain('#[derive(Debug, Serialize, Deserialize)]\npub enum State {');
+        code.should.contain('#[derive(Debug, Serialize, Deserialize, Default)]\npub struct Address {');
+
+        code = generate(hrModel, { derives: MIXED_DERIVES[1] }).get('org_acme_hr_1_0_0.rs');
+        code.should.contain('#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, PartialOrd)]\npub enum State {');
+        code.should.contain('#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd, Default)]\npub struct Address {');
+        code.should.contain('#[derive(Debug, Serialize, Clone, PartialEq)]\n#[serde(transparent)]\npub struct SSN(String);');
+        code.should.contain('#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]\npub struct Employee {');
+    });
+
+    it('should generate string-valued enums', () => {
+        const code = generateModels([ENUM_MODEL]).get('org_acme_status_1_0_0.rs');
+        code.should.contain('pub enum Status {\n   #[serde(rename = "PASSED_TESTING")]\n   PassedTesting,\n   #[serde(rename = "passedTesting")]\n   PassedTesting_,');
+        code.should.contain('#[serde(rename = "self")]\n   Self_,');
+        code.should.contain('#[serde(rename = "_1")]\n   V1,\n   Apple,\n}');
+        code.should.contain('pub const ALL: [Status; 6] = [');
+        code.should.contain('impl std::str::FromStr for Status {');
+        code.should.contain('pub fn default_check_status() -> Status {\n   Status::PassedTesting\n}');
+    });
+
+    it('should write the code of the plugin', () => {
+        const code = generate(hrModel, {}, new DescribePlugin()).get('org_acme_hr_1_0_0.rs');
+        code.should.contain('use crate::lib::utils::*;\nuse std::fmt::Display;\n');
+        code.should.contain('#[allow(clippy::struct_field_names)]\npub struct Address {');
+        code.should.contain('impl Address {\n   pub fn describe(&self) -> impl Display {');
+        code.should.contain('impl State {\n   pub fn describe(&self) -> impl Display {');
+        code.should.contain('pub mod state_values {}');
+        code.should.match(/pub mod description {\n   pub const NAMESPACE: &str = "org.acme.hr@1.0.0";\n}\n$/);
+    });
+
+    it('should generate the serde helpers of DateTime fields', () => {
+        const code = generateModels([DATETIME_MODEL]).get('org_acme_time_1_0_0.rs');
+        code.should.contain('serialize_with = "serialize_datetime",');
+        code.should.contain('default,\n      serialize_with = "serialize_datetime_option",');
+        code.should.contain('serialize_with = "serialize_datetime_vec",');
+        code.should.contain('default,\n      serialize_with = "serialize_datetime_vec_option",');
+        code.should.contain('pub struct Deadline(\n   #[serde(\n      serialize_with = "serialize_datetime",');
+        code.should.contain('pub deadline: Option<Deadline>,');
+        code.should.contain('pub milestones: Option<Vec<Deadline>>,');
+    });
+
+    it('should generate the DateTime type of each time library', () => {
+        const chrono = generateModels([DATETIME_MODEL], { cargo: true });
+        chrono.get('Cargo.toml').should.contain('chrono = ');
+        chrono.get('src/org_acme_time_1_0_0.rs').should.contain('pub start: DateTime<Utc>,');
+
+        const fixedOffset = generateModels([DATETIME_MODEL], { cargo: true, timeLibrary: 'chrono-fixed-offset' });
+        fixedOffset.get('src/org_acme_time_1_0_0.rs').should.contain('pub start: DateTime<FixedOffset>,');
+        fixedOffset.get('src/utils.rs').should.contain('pub fn parse_datetime(value: &str) -> std::result::Result<DateTime<FixedOffset>, chrono::ParseError> {');
+
+        const time = generateModels([DATETIME_MODEL], { cargo: true, timeLibrary: 'time' });
+        time.get('Cargo.toml').should.contain('time = ');
+        time.get('Cargo.toml').should.not.contain('chrono');
+        time.get('src/org_acme_time_1_0_0.rs').should.contain('use time::OffsetDateTime;');
+        time.get('src/org_acme_time_1_0_0.rs').should.contain('pub holidays: Vec<OffsetDateTime>,');
+        time.get('src/utils.rs').should.not.contain('chrono');
+    });
+
+    it('should import the chrono TimeZone trait for DateTime defaults', () => {
+        generateModels([DATETIME_DEFAULT_MODEL]).get('org_acme_time_1_0_0.rs').should.contain('use chrono::{ DateTime, TimeZone, Utc };');
+        generateModels([DATETIME_DEFAULT_MODEL], { timeLibrary: 'chrono-fixed-offset' }).get('org_acme_time_1_0_0.rs').should.contain('use chrono::{ DateTime, FixedOffset, TimeZone };');
+        generateModels([DATETIME_MODEL]).get('org_acme_time_1_0_0.rs').should.not.contain('TimeZone');
+    });
+
+    it('should generate a Cargo crate', () => {
+        const files = generate(hrModel, { cargo: true, crateName: 'hr' });
+        files.get('Cargo.toml').should.contain('name = "hr"');
+        files.get('Cargo.toml').should.contain('serde = { version = "=1.0.229", features = ["derive"] }');
+        files.get('Cargo.toml').should.contain('serde_json = "=1.0.154"');
+        files.get('src/lib.rs').should.contain('pub mod org_acme_hr_1_0_0;');
+        files.get('src/lib.rs').should.contain('pub mod utils;');
+        files.get('src/org_acme_hr_1_0_0.rs').should.contain('use crate::utils::*;');
+        files.has('src/utils.rs').should.equal(true);
+        files.has('mod.rs').should.equal(false);
+    });
+
+    it('should import the generated modules from the configured module root', () => {
+        const code = generate(hrModel, { moduleRoot: 'crate::model' }).get('org_acme_hr_1_0_0.rs');
+        code.should.contain('impl crate::model::concerto_1_0_0::IConcept for Address {}');
+        code.should.contain('use crate::model::utils::*;');
+        code.should.not.contain('crate::lib');
+    });
+
+    it('should import the referenced types and alias colliding names', () => {
+        const code = generateModels(COLLIDING_MODELS).get('org_acme_app_1_0_0.rs');
+        code.should.contain('use crate::lib::org_accordproject_runtime_0_2_0::State as RuntimeState;');
+        code.should.contain('use crate::lib::org_other_runtime_1_0_0::State as OrgOtherRuntime100State;');
+        code.should.not.contain('::*;\nuse crate::lib::utils::*;');
+        code.should.contain('pub state: RuntimeState,');
+        code.should.contain('pub other_state: OrgOtherRuntime100State,');
+        code.should.contain('pub mine: State,');
+        code.should.contain('crate::lib::org_accordproject_runtime_0_2_0::validate_request_request_id(&self.request_id)?;');
+    });
+
+    it('should generate nested modules for versioned namespaces', () => {
+        const files = generateModels(VERSIONED_MODELS, { cargo: true, nestedModules: true });
+        files.get('src/lib.rs').should.contain('pub mod org;');
+        files.get('src/org/mod.rs').should.equal('pub mod acme;\n');
+        files.get('src/org/acme/mod.rs').should.equal('pub mod hr;\npub mod v1_0_0;\n');
+        files.get('src/org/acme/hr/mod.rs').should.equal('pub mod v1_0_0;\npub mod v2_0_0;\n');
+        files.get('src/org/acme/hr/v1_0_0/mod.rs').should.contain('pub struct Person {');
+        const code = files.get('src/org/acme/hr/v2_0_0/mod.rs');
+        code.should.contain('use crate::org::acme::hr::v1_0_0::Person;');
+        code.should.contain('pub struct Employee {');
+        files.get('src/org/acme/v1_0_0/mod.rs').should.contain('use crate::org::acme::hr::v2_0_0::Employee;');
+        files.has('src/org_acme_hr_1_0_0.rs').should.equal(false);
+    });
+
+    it('should generate the system models and the resolved external models', () => {
+        const files = generateModels([COLLIDING_MODELS[0], EXTERNAL_MODEL], { cargo: true });
+        files.get('src/lib.rs').should.contain('pub mod concerto_1_0_0;');
+        files.get('src/lib.rs').should.contain('pub mod org_accordproject_runtime_0_2_0;');
+        files.get('src/concerto_1_0_0.rs').should.contain('pub trait ITransaction');
+        files.get('src/org_accordproject_runtime_0_2_0.rs').should.contain('pub trait IRequest');
+        files.get('src/org_acme_helloworld_1_0_0.rs').should.contain('impl crate::org_accordproject_runtime_0_2_0::IRequest for MyRequest {');
+    });
+
+    it('should only generate the unions of the system types that a property refers to', () => {
+        const hr = generate(hrModel).get('concerto_1_0_0.rs');
+        hr.should.contain('pub trait IConcept');
+        hr.should.not.contain('pub enum ConceptUnion');
+        hr.should.not.contain('pub enum ParticipantUnion');
+        const files = generateModels([SYSTEM_REFERENCE_MODEL]);
+        files.get('concerto_1_0_0.rs').should.contain('pub enum ConceptUnion {');
+        files.get('concerto_1_0_0.rs').should.contain('pub enum ParticipantUnion {');
+        files.get('concerto_1_0_0.rs').should.not.contain('pub enum AssetUnion');
+        files.get('org_acme_holder_1_0_0.rs').should.contain('pub owner: Relationship<ParticipantUnion>,');
+    });
+
+    it('should not generate code for unresolved external models', () => {
+        (() => generateModels([EXTERNAL_MODEL])).should.throw(/Unresolved imports: org.accordproject.runtime@0.2.0 from https:\/\/models.accordproject.org/);
+    });
+
+    it('should generate Rust code that compiles for the HR model', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoCheck(generate(hrModel, { cargo: true }));
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should generate Rust code that passes clippy for the HR model', async function () {
+        if (!hasCargo() || !hasClippy()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargo(generate(hrModel, { cargo: true }), 'clippy');
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should generate Rust code that compiles for the unions of the system types', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoCheck(generateModels([SYSTEM_REFERENCE_MODEL], { cargo: true, derives: DERIVES }));
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should generate Rust code that compiles with the configured derives', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoCheck(generate(hrModel, { cargo: true, derives: DERIVES }));
+        result.status.should.equal(0, result.stderr);
+    });
+
+    MIXED_DERIVES.forEach((derives, index) => {
+        it(`should generate Rust code that compiles with derives configured for each kind ${index + 1}`, async function () {
+            if (!hasCargo()) {
+                this.skip();
+            }
+            this.timeout(600000);
+            const result = await cargoCheck(generate(hrModel, { cargo: true, derives }));
+            result.status.should.equal(0, result.stderr);
+        });
+    });
+
+    it('should generate Rust code that compiles with a plugin', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoCheck(generate(hrModel, { cargo: true }, new DescribePlugin()));
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should round trip DateTime fields', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoTest(generateModels([DATETIME_MODEL], { cargo: true }), { 'datetime.rs': DATETIME_TESTS });
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should round trip string-valued enums', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const files = generateModels([ENUM_MODEL], { cargo: true, derives: DERIVES });
+        const result = await cargoTest(files, { 'enums.rs': ENUM_TESTS });
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should only parse relationships to the classes of their target type', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoTest(generateModels([RELATIONSHIP_MODEL], { cargo: true }), { 'relationships.rs': RELATIONSHIP_TESTS });
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should validate scalars and fields', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoTest(generateModels([VALIDATION_MODEL], { cargo: true, derives: { struct: ['Clone'], scalar: ['Clone'] } }), { 'validation.rs': VALIDATION_TESTS });
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should round trip DateTime fields with the time library', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const files = generateModels([DATETIME_MODEL], { cargo: true, timeLibrary: 'time' });
+        const result = await cargoTest(files, { 'datetime.rs': DATETIME_TESTS });
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should keep the offset of DateTime fields with chrono-fixed-offset', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const files = generateModels([DATETIME_MODEL], { cargo: true, timeLibrary: 'chrono-fixed-offset' });
+        const result = await cargoTest(files, { 'datetime.rs': FIXED_OFFSET_TESTS });
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should read the DateTime values of concerto-core, and write values that concerto-core reads', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const modelManager = new ModelManager();
+        modelManager.addCTOModel(DATETIME_MODEL, 'model.cto');
+        const serializer = new Serializer(new Factory(modelManager), modelManager);
+        const schedule = JSON.stringify(serializer.toJSON(serializer.fromJSON({
+            $class: 'org.acme.time@1.0.0.Schedule',
+            start: '2024-01-02T05:04:05.678+02:00',
+            holidays: ['2024-12-25T00:00:00.000Z'],
+        })));
+        const rustDateTime = '2024-01-02T03:04:05.678Z';
+        const tests = `use concerto_model::org_acme_time_1_0_0::Schedule;
+
+#[test]
+fn reads_and_writes_concerto_datetimes() {
+    let schedule: Schedule = serde_json::from_str(${JSON.stringify(schedule)}).unwrap();
+    let value = serde_json::to_value(&schedule).unwrap();
+    assert_eq!(value["start"], "${rustDateTime}");
+}
+`;
+        const result = await cargoTest(generateModels([DATETIME_MODEL], { cargo: true }), { 'concerto.rs': tests });
+        result.status.should.equal(0, result.stderr);
+
+        const resource = serializer.fromJSON({ $class: 'org.acme.time@1.0.0.Schedule', start: rustDateTime, holidays: [] });
+        resource.start.toISOString().should.equal(rustDateTime);
+    });
+
+    ['chrono', 'chrono-fixed-offset'].forEach(timeLibrary => {
+        it(`should generate Rust code that compiles for DateTime defaults with ${timeLibrary}`, async function () {
+            if (!hasCargo()) {
+                this.skip();
+            }
+            this.timeout(600000);
+            const result = await cargoCheck(generateModels([DATETIME_DEFAULT_MODEL], { cargo: true, timeLibrary }));
+            result.status.should.equal(0, result.stderr);
+        });
+    });
+
+    it('should generate Rust code that compiles for a circular model', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoCheck(generate(circularModel, { cargo: true }));
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should generate Rust code that compiles for recursive models', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoCheck(generateModels([RECURSIVE_MODEL], { cargo: true }));
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should generate Rust code that compiles for namespaces with colliding names', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoCheck(generateModels(COLLIDING_MODELS, { cargo: true }));
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should generate Rust code that compiles for resolved external models', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoCheck(generateModels([COLLIDING_MODELS[0], EXTERNAL_MODEL], { cargo: true }));
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should generate Rust code that compiles for types named like the generated types', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoCheck(generateModels([SHADOWING_MODEL], { cargo: true }));
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should generate Rust code that compiles for nested versioned modules', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoCheck(generateModels(VERSIONED_MODELS, { cargo: true, nestedModules: true }));
+        result.status.should.equal(0, result.stderr);
+    });
+
+    it('should generate Rust code that compiles for every Concerto primitive type', async function () {
+        if (!hasCargo()) {
+            this.skip();
+        }
+        this.timeout(600000);
+        const result = await cargoCheck(generate(primitivesModel, { cargo: true }));
+        result.status.should.equal(0, result.stderr);
+    });
+});
@@ -17,6 +17,9 @@
 const chai = require('chai');
 chai.should();
 const sinon = require('sinon');
+const fs = require('fs');
+const path = require('path');
+const tmp = require('tmp-promise');
 
 const RustVisitor = require('../../../../lib/codegen/fromcto/rust/rustvisitor');
 
@@ -27,6 +30,7 @@ const Field = require('@accordproject/concerto-core').Field;
 const ModelFile = require('@accordproject/concerto-core').ModelFile;
 const ModelManager = require('@accordproject/concerto-core').ModelManager;
 const RelationshipDeclaration = require('@accordproject/concerto-core').RelationshipDeclaration;
+const ScalarDeclaration = require('@accordproject/concerto-core').ScalarDeclaration;
 const FileWriter = require('@accordproject/concerto-util').FileWriter;
 
 
@@ -114,6 +118,30 @@ describe('RustVisitor', function () {
         });
 
 
+        it('should return visitScalarDeclaration for a ScalarDeclaration', () => {
+            let thing = sinon.createStubInstance(ScalarDeclaration);
+            thing.isScalarDeclaration.returns(true);
+            let mockSpecialVisit = sinon.stub(rustVisitor, 'visitScalarDeclaration');
+            mockSpecialVisit.returns('Duck');
+
+            rustVisitor.visit(thing, param).should.deep.equal('Duck');
+
+            mockSpecialVisit.calledWith(thing, param).should.be.ok;
+        });
+
+        it('should return visitField for a Field typed by a scalar', () => {
+            let thing = sinon.createStubInstance(Field);
+            thing.isField.returns(true);
+            thing.isTypeScalar.returns(true);
+            let mockSpecialVisit = sinon.stub(rustVisitor, 'visitField');
+            mockSpecialVisit.returns('Duck');
+
+            rustVisitor.visit(thing, param).should.deep.equal('Duck');
+
+            mockSpecialVisit.calledWith(thing, param).should.be.ok;
+            thing.getScalarField.called.should.be.false;
+        });
+
         it('should return visitEnumValueDeclaration for a EnumValueDeclaration', () => {
             let thing = sinon.createStubInstance(EnumValueDeclaration);
             thing.isEnumValue.returns(true);
@@ -124,229 +152,2181 @@ describe('RustVisitor', function () {
 
             mockSpecialVisit.calledWith(thing, param).should.be.ok;
         });
-    });
-
-    describe('visitModelManager', () => {
-        it('should call accept for each model file', () => {
-            let acceptSpy = sinon.spy();
+    });
+
+    describe('visitModelManager', () => {
+        it('should call accept for each model file', () => {
+            let acceptSpy = sinon.spy();
+
+            let param = {
+                fileWriter: mockFileWriter
+            };
+
+            sinon.stub(rustVisitor, 'addUtilsModelFile');
+
+
+            let mockModelManager = sinon.createStubInstance(ModelManager);
+            mockModelManager.isModelManager.returns(true);
+            mockModelManager.getModelFiles.returns([{
+                accept: acceptSpy,
+                getAst: () => ({ imports: [] })
+            },
+            {
+                accept: acceptSpy,
+                getAst: () => ({ imports: [] })
+            }
+            ]);
+
+            mockModelManager.getNamespaces.returns([
+                'Goose'
+            ]);
+
+            rustVisitor.visitModelManager(mockModelManager, param);
+            acceptSpy.withArgs(rustVisitor, param).calledTwice.should.be.ok;
+            param.fileWriter.openFile.withArgs('mod.rs').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, 'pub mod goose;').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, 'pub mod utils;').calledOnce.should.be.ok;
+            param.fileWriter.closeFile.calledOnce.should.be.ok;
+        });
+
+        it('should write a Cargo crate', () => {
+            let param = {
+                fileWriter: mockFileWriter,
+                cargo: true
+            };
+
+            sinon.stub(rustVisitor, 'addUtilsModelFile');
+            let mockAddCargoManifest = sinon.stub(rustVisitor, 'addCargoManifest');
+
+            let mockModelManager = sinon.createStubInstance(ModelManager);
+            mockModelManager.isModelManager.returns(true);
+            mockModelManager.getModelFiles.returns([]);
+            mockModelManager.getNamespaces.returns([
+                'Goose'
+            ]);
+
+            rustVisitor.visitModelManager(mockModelManager, param);
+            mockAddCargoManifest.calledWith(param).should.be.ok;
+            param.fileWriter.openFile.withArgs('src/lib.rs').calledOnce.should.be.ok;
+            param.fileWriter.openFile.withArgs('mod.rs').called.should.equal(false);
+            param.fileWriter.writeLine.withArgs(0, 'pub mod goose;').calledOnce.should.be.ok;
+        });
+
+        it('should write the intermediate modules of nested modules', () => {
+            let param = {
+                fileWriter: mockFileWriter,
+                nestedModules: true
+            };
+
+            sinon.stub(rustVisitor, 'addUtilsModelFile');
+
+            let mockModelManager = sinon.createStubInstance(ModelManager);
+            mockModelManager.isModelManager.returns(true);
+            mockModelManager.getModelFiles.returns([]);
+            mockModelManager.getNamespaces.returns([
+                'org.acme.hr@1.0.0',
+                'org.acme.hr@2.0.0',
+                'org.acme',
+                'concerto@1.0.0'
+            ]);
+
+            rustVisitor.visitModelManager(mockModelManager, param);
+            param.fileWriter.openFile.getCalls().map(call => call.args[0]).should.deep.equal([
+                'mod.rs',
+                'org/mod.rs',
+                'org/acme/hr/mod.rs',
+                'concerto/mod.rs'
+            ]);
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'pub mod org;'],
+                [0, 'pub mod concerto;'],
+                [0, 'pub mod utils;'],
+                [0, 'pub mod acme;'],
+                [0, 'pub mod v1_0_0;'],
+                [0, 'pub mod v2_0_0;'],
+                [0, 'pub mod v1_0_0;']
+            ]);
+        });
+    });
+
+    describe('getUnresolvedImports', () => {
+        it('should return the imports of namespaces that are not in the model manager', () => {
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('org.acme.helloworld@1.0.0');
+            mockModelFile.getAst.returns({
+                imports: [
+                    { namespace: 'org.acme.base@1.0.0', name: 'Base' },
+                    { namespace: 'org.acme.runtime@1.0.0', types: ['Request', 'Response'], uri: 'https://models.acme.org/runtime@1.0.0.cto' },
+                ]
+            });
+            let mockModelManager = sinon.createStubInstance(ModelManager);
+            mockModelManager.getModelFiles.returns([mockModelFile]);
+            mockModelManager.getNamespaces.returns(['org.acme.helloworld@1.0.0', 'org.acme.base@1.0.0']);
+
+            rustVisitor.getUnresolvedImports(mockModelManager).should.deep.equal([{
+                importer: 'org.acme.helloworld@1.0.0',
+                namespace: 'org.acme.runtime@1.0.0',
+                uri: 'https://models.acme.org/runtime@1.0.0.cto'
+            }]);
+        });
+
+        it('should return no imports when every imported namespace is in the model manager', () => {
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('org.acme.helloworld@1.0.0');
+            mockModelFile.getAst.returns({ imports: [{ namespace: 'org.acme.base@1.0.0', name: 'Base' }] });
+            let mockModelManager = sinon.createStubInstance(ModelManager);
+            mockModelManager.getModelFiles.returns([mockModelFile]);
+            mockModelManager.getNamespaces.returns(['org.acme.helloworld@1.0.0', 'org.acme.base@1.0.0']);
+
+            rustVisitor.getUnresolvedImports(mockModelManager).should.deep.equal([]);
+        });
+
+        it('should prevent generating code for models with unresolved imports', () => {
+            sinon.stub(rustVisitor, 'getUnresolvedImports').returns([
+                { importer: 'org.acme.helloworld@1.0.0', namespace: 'org.acme.runtime@1.0.0', uri: 'https://models.acme.org/runtime@1.0.0.cto' },
+                { importer: 'org.acme.helloworld@1.0.0', namespace: 'org.acme.base@1.0.0' },
+            ]);
+            let mockModelManager = sinon.createStubInstance(ModelManager);
+
+            (() => rustVisitor.visitModelManager(mockModelManager, { fileWriter: mockFileWriter }))
+                .should.throw('Unresolved imports: org.acme.runtime@1.0.0 from https://models.acme.org/runtime@1.0.0.cto (imported by org.acme.helloworld@1.0.0), ' +
+                    'org.acme.base@1.0.0 (imported by org.acme.helloworld@1.0.0).');
+            mockFileWriter.openFile.called.should.equal(false);
+        });
+    });
+
+    describe('resolveExternalModels', () => {
+        const HELLO_MODEL = `namespace org.acme.helloworld@1.0.0
+import org.acme.base@1.0.0.Base from https://models.acme.org/base@1.0.0.cto
+concept Greeting extends Base {
+    o String message
+}`;
+        const BASE_MODEL = `namespace org.acme.base@1.0.0
+abstract concept Base {
+}`;
+        let modelManager;
+        let cacheDirectory;
+        let cleanup;
+        beforeEach(async () => {
+            modelManager = new ModelManager();
+            modelManager.addCTOModel(HELLO_MODEL, 'hello.cto', true);
+            sinon.stub(modelManager, 'updateExternalModels').callsFake(async () => {
+                modelManager.addCTOModel(BASE_MODEL, '@models.acme.org/base@1.0.0.cto', true);
+            });
+            ({ path: cacheDirectory, cleanup } = await tmp.dir({ unsafeCleanup: true }));
+        });
+        afterEach(async () => {
+            await cleanup();
+        });
+
+        it('should add the models of the cache directory', async () => {
+            fs.writeFileSync(path.join(cacheDirectory, 'org.acme.base@1.0.0.cto'), BASE_MODEL);
+
+            const unresolvedImports = await rustVisitor.resolveExternalModels(modelManager, { cacheDirectory });
+
+            unresolvedImports.should.deep.equal([]);
+            modelManager.getNamespaces().should.include('org.acme.base@1.0.0');
+            modelManager.updateExternalModels.called.should.equal(false);
+        });
+
+        it('should download the unresolved imports and save them to the cache directory', async () => {
+            const directory = path.join(cacheDirectory, 'models');
+
+            const unresolvedImports = await rustVisitor.resolveExternalModels(modelManager, { cacheDirectory: directory });
+
+            unresolvedImports.should.deep.equal([]);
+            modelManager.updateExternalModels.calledOnce.should.equal(true);
+            fs.readdirSync(directory).should.deep.equal(['org.acme.base@1.0.0.cto']);
+            fs.readFileSync(path.join(directory, 'org.acme.base@1.0.0.cto'), 'utf8').should.equal(BASE_MODEL);
+        });
+
+        it('should download the unresolved imports without a cache directory', async () => {
+            const unresolvedImports = await rustVisitor.resolveExternalModels(modelManager);
+
+            unresolvedImports.should.deep.equal([]);
+            modelManager.updateExternalModels.calledOnce.should.equal(true);
+        });
+
+        it('should return the imports that are not in the cache directory when offline', async () => {
+            const unresolvedImports = await rustVisitor.resolveExternalModels(modelManager, { cacheDirectory, offline: true });
+
+            unresolvedImports.should.deep.equal([{
+                importer: 'org.acme.helloworld@1.0.0',
+                namespace: 'org.acme.base@1.0.0',
+                uri: 'https://models.acme.org/base@1.0.0.cto'
+            }]);
+            modelManager.updateExternalModels.called.should.equal(false);
+            fs.readdirSync(cacheDirectory).should.deep.equal([]);
+        });
+
+        it('should not load or download models when every import is resolved', async () => {
+            modelManager.addCTOModel(BASE_MODEL, 'base.cto', true);
+            fs.writeFileSync(path.join(cacheDirectory, 'org.acme.other@1.0.0.cto'), 'namespace org.acme.other@1.0.0');
+
+            const unresolvedImports = await rustVisitor.resolveExternalModels(modelManager, { cacheDirectory });
+
+            unresolvedImports.should.deep.equal([]);
+            modelManager.getNamespaces().should.not.include('org.acme.other@1.0.0');
+            modelManager.updateExternalModels.called.should.equal(false);
+        });
+    });
+
+    describe('toRustModuleSegments', () => {
+        it('should map a namespace to a single module', () => {
+            rustVisitor.toRustModuleSegments('org.acme.hr@1.0.0', {}).should.deep.equal(['org_acme_hr_1_0_0']);
+        });
+
+        it('should map each part of a namespace and its version to nested modules', () => {
+            const param = { nestedModules: true };
+            rustVisitor.toRustModuleSegments('org.acme.hr@1.0.0', param).should.deep.equal(['org', 'acme', 'hr', 'v1_0_0']);
+            rustVisitor.toRustModuleSegments('org.acme.hr@2.0.0-beta.1', param).should.deep.equal(['org', 'acme', 'hr', 'v2_0_0_beta_1']);
+            rustVisitor.toRustModuleSegments('org.type.acme', param).should.deep.equal(['org', 'type_', 'acme']);
+        });
+    });
+
+    describe('getChildModules', () => {
+        it('should return the modules directly contained in a module', () => {
+            const namespaces = ['org.acme.hr@1.0.0', 'org.acme.hr@2.0.0', 'org.acme', 'com.acme'];
+            const param = { nestedModules: true };
+            rustVisitor.getChildModules(namespaces, [], param).should.deep.equal(['org', 'com']);
+            rustVisitor.getChildModules(namespaces, ['org', 'acme'], param).should.deep.equal(['hr']);
+            rustVisitor.getChildModules(namespaces, ['org', 'acme', 'hr'], param).should.deep.equal(['v1_0_0', 'v2_0_0']);
+            rustVisitor.getChildModules(namespaces, ['org', 'acme', 'hr', 'v1_0_0'], param).should.deep.equal([]);
+        });
+
+        it('should return a flat module for each namespace', () => {
+            rustVisitor.getChildModules(['org.acme@1.0.0', 'concerto'], [], {}).should.deep.equal(['org_acme_1_0_0', 'concerto']);
+        });
+    });
+
+    describe('addCargoManifest', () => {
+        it('should write the Cargo.toml of the crate', () => {
+            let param = {
+                fileWriter: mockFileWriter,
+                crateName: 'hr_model',
+                crateVersion: '1.2.3'
+            };
+            rustVisitor.addCargoManifest(param);
+
+            param.fileWriter.openFile.withArgs('Cargo.toml').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, '[package]'],
+                [0, 'name = "hr_model"'],
+                [0, 'version = "1.2.3"'],
+                [0, 'edition = "2021"'],
+                [0, ''],
+                [0, '[dependencies]'],
+                [0, 'chrono = { version = "=0.4.45", features = ["serde"] }'],
+                [0, 'regex = "=1.13.1"'],
+                [0, 'serde = { version = "=1.0.229", features = ["derive"] }'],
+                [0, 'serde_json = "=1.0.154"'],
+            ]);
+            param.fileWriter.closeFile.calledOnce.should.be.ok;
+        });
+
+        it('should default the name and version of the crate', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            rustVisitor.addCargoManifest(param);
+
+            param.fileWriter.writeLine.withArgs(0, 'name = "concerto_model"').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, 'version = "0.1.0"').calledOnce.should.be.ok;
+        });
+
+        it('should depend on the time library of the DateTime type', () => {
+            let param = {
+                fileWriter: mockFileWriter,
+                timeLibrary: 'time'
+            };
+            rustVisitor.addCargoManifest(param);
+
+            const lines = param.fileWriter.writeLine.getCalls().map(call => call.args[1]);
+            lines.slice(-4).should.deep.equal([
+                'regex = "=1.13.1"',
+                'serde = { version = "=1.0.229", features = ["derive"] }',
+                'serde_json = "=1.0.154"',
+                'time = { version = "=0.3.55", features = ["parsing"] }',
+            ]);
+            lines.should.not.include('chrono = { version = "=0.4.45", features = ["serde"] }');
+        });
+    });
+
+    describe('toRustModulePath', () => {
+        it('should reference the modules of a Cargo crate from its root', () => {
+            rustVisitor.toRustModulePath('org.acme@1.0.0', {}).should.equal('crate::lib::org_acme_1_0_0');
+            rustVisitor.toRustModulePath('org.acme@1.0.0', { cargo: true }).should.equal('crate::org_acme_1_0_0');
+        });
+
+        it('should reference the modules from the configured module root', () => {
+            rustVisitor.toRustModulePath('org.acme@1.0.0', { moduleRoot: 'crate::model' }).should.equal('crate::model::org_acme_1_0_0');
+            rustVisitor.toRustModulePath('org.acme@1.0.0', { moduleRoot: 'crate' }).should.equal('crate::org_acme_1_0_0');
+            rustVisitor.toRustModulePath('org.acme@1.0.0', { moduleRoot: 'hr_model::' }).should.equal('hr_model::org_acme_1_0_0');
+            rustVisitor.toRustModulePath('org.acme@1.0.0', { cargo: true, moduleRoot: 'crate::model' }).should.equal('crate::model::org_acme_1_0_0');
+        });
+
+        it('should reference nested modules', () => {
+            rustVisitor.toRustModulePath('org.acme@1.0.0', { cargo: true, nestedModules: true }).should.equal('crate::org::acme::v1_0_0');
+            rustVisitor.toRustModulePath('org.acme', { moduleRoot: 'hr_model', nestedModules: true }).should.equal('hr_model::org::acme');
+        });
+    });
+
+    describe('visitModelFile', () => {
+        let param;
+        beforeEach(() => {
+            param = {
+                fileWriter: mockFileWriter
+            };
+        });
+        /**
+         * Returns a property stub typed by a type of a namespace.
+         * @param {string} fqn - the fully qualified name of the type
+         * @param {boolean} [primitive] - true if the type is a primitive
+         * @return {Object} the property stub
+         */
+        const toProperty = (fqn, primitive) => ({
+            isPrimitive: () => !!primitive,
+            getFullyQualifiedTypeName: () => fqn
+        });
+
+        it('should import the types of other namespaces referenced by the fields', () => {
+            let acceptSpy = sinon.spy();
+            let mockEnum = sinon.createStubInstance(EnumDeclaration);
+            mockEnum.isEnum.returns(true);
+            mockEnum.getName.returns('Colour');
+            mockEnum.getProperties.returns([]);
+            mockEnum.accept = acceptSpy;
+
+            let mockScalar = sinon.createStubInstance(ScalarDeclaration);
+            mockScalar.isScalarDeclaration.returns(true);
+            mockScalar.getName.returns('Code');
+            mockScalar.accept = acceptSpy;
+
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.isClassDeclaration.returns(true);
+            mockClassDeclaration.isAbstract.returns(false);
+            mockClassDeclaration.getDirectSubclasses.returns([]);
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getProperties.returns([
+                toProperty('org.org1.Property1'),
+                toProperty('org.acme.Property2'),
+                toProperty('String', true),
+                toProperty('org.org1.Property3'),
+                toProperty('org.org1.Property3'),
+            ]);
+            mockClassDeclaration.accept = sinon.spy((visitor, parameters) => {
+                parameters.fileWriter.writeLine(0, '#[derive(Debug, Serialize, Deserialize)]');
+                parameters.fileWriter.writeLine(0, 'pub struct Bob {');
+                parameters.fileWriter.writeLine(1, 'pub dob: DateTime<Utc>,');
+                parameters.fileWriter.writeLine(0, '}');
+                parameters.fileWriter.writeLine(0, 'impl ConcertoClass for Bob {}');
+            });
+
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('org.acme');
+            mockModelFile.getAllDeclarations.returns([
+                mockEnum,
+                mockScalar,
+                mockClassDeclaration
+            ]);
+            sinon.stub(rustVisitor, 'getUnionDeclaration').returns(null);
+
+            rustVisitor.visitModelFile(mockModelFile, param);
+
+            param.fileWriter.openFile.withArgs('org_acme.rs').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'use serde::{ Deserialize, Serialize };'],
+                [0, 'use chrono::{ DateTime, Utc };'],
+                [1, ''],
+                [0, 'use crate::lib::org_org1::Property1;'],
+                [0, 'use crate::lib::org_org1::Property3;'],
+                [0, 'use crate::lib::utils::*;'],
+            ]);
+            param.fileWriter.write.calledWith([
+                '   ',
+                '#[derive(Debug, Serialize, Deserialize)]',
+                'pub struct Bob {',
+                '   pub dob: DateTime<Utc>,',
+                '}',
+                'impl ConcertoClass for Bob {}',
+                '',
+            ].join('\n')).should.be.ok;
+            param.fileWriter.closeFile.calledOnce.should.be.ok;
+
+            acceptSpy.callCount.should.equal(2);
+            mockClassDeclaration.accept.calledOnce.should.be.ok;
+            const fileParameters = acceptSpy.firstCall.args[1];
+            fileParameters.fileWriter.should.not.equal(param.fileWriter);
+            fileParameters.namespace.should.equal('org.acme');
+            fileParameters.importedTypes.get('org.org1.Property1').should.equal('Property1');
+        });
+
+        it('should only import what the module uses', () => {
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.isClassDeclaration.returns(true);
+            mockClassDeclaration.isAbstract.returns(true);
+            mockClassDeclaration.getName.returns('Concept');
+            mockClassDeclaration.getOwnProperties.returns([]);
+            mockClassDeclaration.accept = (visitor, parameters) => {
+                parameters.fileWriter.writeLine(0, 'pub trait IConcept {}');
+            };
+
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('concerto');
+            mockModelFile.getAllDeclarations.returns([mockClassDeclaration]);
+
+            rustVisitor.visitModelFile(mockModelFile, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [1, ''],
+            ]);
+            param.fileWriter.write.calledWith('   \npub trait IConcept {}\n').should.be.ok;
+        });
+
+        it('should import the serde traits whose methods are called', () => {
+            let mockScalar = sinon.createStubInstance(ScalarDeclaration);
+            mockScalar.isScalarDeclaration.returns(true);
+            mockScalar.getName.returns('Code');
+            mockScalar.accept = (visitor, parameters) => {
+                parameters.fileWriter.writeLine(0, 'let value = String::deserialize(deserializer)?;');
+                parameters.fileWriter.writeLine(0, 'serializer.serialize_str(&value)');
+            };
+
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('org.acme');
+            mockModelFile.getAllDeclarations.returns([mockScalar]);
+
+            rustVisitor.visitModelFile(mockModelFile, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'use serde::Deserialize;'],
+                [1, ''],
+            ]);
+        });
+
+        it('should alias imported types whose names collide', () => {
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.isClassDeclaration.returns(true);
+            mockClassDeclaration.isAbstract.returns(false);
+            mockClassDeclaration.getDirectSubclasses.returns([]);
+            mockClassDeclaration.getName.returns('State');
+            mockClassDeclaration.getProperties.returns([
+                toProperty('org.accordproject.runtime@0.2.0.State'),
+                toProperty('org.accordproject.runtime@0.2.0.Request'),
+                toProperty('org.other@1.0.0.Request'),
+                toProperty('org.acme.other@1.0.0.Request'),
+                toProperty('org.other@1.0.0.Relationship'),
+            ]);
+
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('org.acme@1.0.0');
+            mockModelFile.getAllDeclarations.returns([mockClassDeclaration]);
+            sinon.stub(rustVisitor, 'getUnionDeclaration').returns(null);
+
+            rustVisitor.getImportedTypes(mockModelFile).map(type => type.alias).should.deep.equal([
+                'RuntimeRequest',
+                'RuntimeState',
+                'OtherRequest',
+                'OtherRelationship',
+                'OrgOther100Request',
+            ]);
+        });
+
+        it('should only import the types of the own fields of an abstract class', () => {
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.isClassDeclaration.returns(true);
+            mockClassDeclaration.isAbstract.returns(true);
+            mockClassDeclaration.getName.returns('Equipment');
+            mockClassDeclaration.getOwnProperties.returns([toProperty('org.org1.Owner')]);
+            mockClassDeclaration.getProperties.returns([toProperty('org.org1.Owner'), toProperty('org.org2.Base')]);
+
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('org.acme');
+            mockModelFile.getAllDeclarations.returns([mockClassDeclaration]);
+            sinon.stub(rustVisitor, 'getUnionDeclaration').returns(null);
+
+            rustVisitor.getImportedTypes(mockModelFile).should.deep.equal([
+                { namespace: 'org.org1', name: 'Owner', alias: 'Owner' }
+            ]);
+        });
+
+        it('should not read the values of enums as fields', () => {
+            let mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
+            mockEnumDeclaration.isClassDeclaration.returns(true);
+            mockEnumDeclaration.isEnum.returns(true);
+            mockEnumDeclaration.getName.returns('State');
+            mockEnumDeclaration.getProperties.returns([toProperty('org.org1.Owner')]);
+
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('org.acme');
+            mockModelFile.getAllDeclarations.returns([mockEnumDeclaration]);
+
+            rustVisitor.getImportedTypes(mockModelFile).should.deep.equal([]);
+            mockEnumDeclaration.getProperties.called.should.equal(false);
+        });
+
+        it('should import the unions of super types', () => {
+            let mockEquipment = sinon.createStubInstance(ClassDeclaration);
+            mockEquipment.getName.returns('Equipment');
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.isClassDeclaration.returns(true);
+            mockClassDeclaration.isAbstract.returns(false);
+            mockClassDeclaration.getDirectSubclasses.returns([]);
+            mockClassDeclaration.getName.returns('Company');
+            mockClassDeclaration.getProperties.returns([toProperty('org.assets.Equipment')]);
+
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('org.acme');
+            mockModelFile.getAllDeclarations.returns([mockClassDeclaration]);
+            sinon.stub(rustVisitor, 'getUnionDeclaration').returns(mockEquipment);
+
+            rustVisitor.getImportedTypes(mockModelFile).should.deep.equal([
+                { namespace: 'org.assets', name: 'EquipmentUnion', alias: 'EquipmentUnion' }
+            ]);
+        });
+
+        it('should import the DateTime type of the time library', () => {
+            param.timeLibrary = 'time';
+
+            let mockScalar = sinon.createStubInstance(ScalarDeclaration);
+            mockScalar.isScalarDeclaration.returns(true);
+            mockScalar.getName.returns('When');
+            mockScalar.accept = (visitor, parameters) => {
+                parameters.fileWriter.writeLine(0, '#[derive(Debug, Serialize)]');
+                parameters.fileWriter.writeLine(0, 'pub struct When(pub OffsetDateTime);');
+            };
+
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('org.acme');
+            mockModelFile.getAllDeclarations.returns([mockScalar]);
+
+            rustVisitor.visitModelFile(mockModelFile, param);
+
+            param.fileWriter.writeLine.getCalls().slice(0, 2).map(call => call.args).should.deep.equal([
+                [0, 'use serde::Serialize;'],
+                [0, 'use time::OffsetDateTime;'],
+            ]);
+        });
+
+        it('should write a nested module and declare its child modules', () => {
+            param.nestedModules = true;
+            param.cargo = true;
+
+            let mockModelManager = sinon.createStubInstance(ModelManager);
+            mockModelManager.getNamespaces.returns(['org.acme', 'org.acme.hr@1.0.0', 'org.acme.hr@2.0.0', 'org.acme.payroll']);
+
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('org.acme');
+            mockModelFile.getAllDeclarations.returns([]);
+            mockModelFile.getModelManager.returns(mockModelManager);
+
+            rustVisitor.visitModelFile(mockModelFile, param);
+
+            param.fileWriter.openFile.withArgs('src/org/acme/mod.rs').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.getCalls().slice(0, 3).map(call => call.args).should.deep.equal([
+                [0, 'pub mod hr;'],
+                [0, 'pub mod payroll;'],
+                [1, '']
+            ]);
+        });
+    });
+
+    describe('toTypeReference', () => {
+        const property = {
+            isPrimitive: () => false,
+            getFullyQualifiedTypeName: () => 'org.runtime@0.2.0.State'
+        };
+
+        it('should refer to types by name outside of a module', () => {
+            rustVisitor.toTypeReference(property, 'State', {}).should.equal('State');
+        });
+
+        it('should refer to local types by name', () => {
+            rustVisitor.toTypeReference(property, 'State', { namespace: 'org.runtime@0.2.0' }).should.equal('State');
+        });
+
+        it('should refer to imported types by their alias', () => {
+            const parameters = {
+                namespace: 'org.acme@1.0.0',
+                importedTypes: new Map([['org.runtime@0.2.0.State', 'RuntimeState']])
+            };
+            rustVisitor.toTypeReference(property, 'State', parameters).should.equal('RuntimeState');
+        });
+
+        it('should fully qualify types that are not imported', () => {
+            const parameters = {
+                namespace: 'org.acme@1.0.0',
+                importedTypes: new Map()
+            };
+            rustVisitor.toTypeReference(property, 'State', parameters).should.equal('crate::lib::org_runtime_0_2_0::State');
+        });
+    });
+
+    describe('toFieldFunctionPath', () => {
+        it('should qualify the functions of fields inherited from another namespace', () => {
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Request');
+            mockClassDeclaration.getNamespace.returns('org.runtime@0.2.0');
+            let mockField = sinon.createStubInstance(Field);
+            mockField.getName.returns('requestId');
+            mockField.getParent.returns(mockClassDeclaration);
+
+            rustVisitor.toFieldFunctionPath('validate', mockField, {}).should.equal('validate_request_request_id');
+            rustVisitor.toFieldFunctionPath('validate', mockField, { namespace: 'org.runtime@0.2.0' }).should.equal('validate_request_request_id');
+            rustVisitor.toFieldFunctionPath('validate', mockField, { namespace: 'org.acme' }).should.equal('crate::lib::org_runtime_0_2_0::validate_request_request_id');
+        });
+    });
+
+
+    describe('visitEnumDeclaration', () => {
+        it('should write the export enum and call accept on each property', () => {
+            let acceptSpy = sinon.spy();
+
+            let param = {
+                fileWriter: mockFileWriter
+            };
+
+            let mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
+            mockEnumDeclaration.isEnum.returns(true);
+            mockEnumDeclaration.getName.returns('Bob');
+            mockEnumDeclaration.getOwnProperties.returns([{
+                accept: acceptSpy
+            },
+            {
+                accept: acceptSpy
+            }]);
+            let mockWriteEnumImpls = sinon.stub(rustVisitor, 'writeEnumImpls');
+
+            rustVisitor.visitEnumDeclaration(mockEnumDeclaration, param);
+
+            param.fileWriter.writeLine.callCount.should.deep.equal(3);
+            param.fileWriter.writeLine.withArgs(0, 'pub enum Bob {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, '}\n').calledOnce.should.be.ok;
+
+            acceptSpy.withArgs(rustVisitor, param).calledTwice.should.be.ok;
+            mockWriteEnumImpls.calledWith(mockEnumDeclaration, param).should.be.ok;
+        });
+
+        it('should write the configured derives and attributes of enums', () => {
+            let param = {
+                fileWriter: mockFileWriter,
+                derives: { enum: ['Clone', 'Copy', 'PartialEq', 'Eq', 'Hash'], struct: ['Default'] },
+                attributes: { enum: ['#[non_exhaustive]'] }
+            };
+            let mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
+            mockEnumDeclaration.isEnum.returns(true);
+            mockEnumDeclaration.getName.returns('Bob');
+            mockEnumDeclaration.getOwnProperties.returns([]);
+            sinon.stub(rustVisitor, 'writeEnumImpls');
+
+            rustVisitor.visitEnumDeclaration(mockEnumDeclaration, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, '#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]'],
+                [0, '#[non_exhaustive]'],
+                [0, 'pub enum Bob {'],
+                [0, '}\n'],
+            ]);
+        });
+    });
+
+    describe('writeEnumImpls', () => {
+        it('should write the ALL constant, as_str, Display and FromStr', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            let mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
+            mockEnumDeclaration.getName.returns('Status');
+            mockEnumDeclaration.getOwnProperties.returns(['PASSED_TESTING', 'Failed'].map(name => {
+                const mockEnumValueDeclaration = sinon.createStubInstance(EnumValueDeclaration);
+                mockEnumValueDeclaration.getName.returns(name);
+                return mockEnumValueDeclaration;
+            }));
+
+            rustVisitor.writeEnumImpls(mockEnumDeclaration, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'impl Status {'],
+                [1, 'pub const ALL: [Status; 2] = ['],
+                [2, 'Status::PassedTesting,'],
+                [2, 'Status::Failed,'],
+                [1, '];'],
+                [0, ''],
+                [1, 'pub fn as_str(&self) -> &\'static str {'],
+                [2, 'match *self {'],
+                [3, 'Status::PassedTesting => "PASSED_TESTING",'],
+                [3, 'Status::Failed => "Failed",'],
+                [2, '}'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+                [0, 'impl std::fmt::Display for Status {'],
+                [1, 'fn fmt(&self, f: &mut std::fmt::Formatter<\'_>) -> std::fmt::Result {'],
+                [2, 'f.write_str(self.as_str())'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+                [0, 'impl std::str::FromStr for Status {'],
+                [1, 'type Err = EnumError;'],
+                [0, ''],
+                [1, 'fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {'],
+                [2, 'match value {'],
+                [3, '"PASSED_TESTING" => Ok(Status::PassedTesting),'],
+                [3, '"Failed" => Ok(Status::Failed),'],
+                [3, '_ => Err(EnumError::new("Status", value)),'],
+                [2, '}'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+    });
+
+    describe('getEnumVariants', () => {
+        it('should convert the values to UpperCamelCase variants', () => {
+            rustVisitor.toRustVariantName('PASSED_TESTING').should.equal('PassedTesting');
+            rustVisitor.toRustVariantName('passedTesting').should.equal('PassedTesting');
+            rustVisitor.toRustVariantName('HTTPServer').should.equal('HTTPServer');
+            rustVisitor.toRustVariantName('MA').should.equal('Ma');
+            rustVisitor.toRustVariantName('type').should.equal('Type');
+            rustVisitor.toRustVariantName('_1').should.equal('V1');
+            rustVisitor.toRustVariantName('$').should.equal('V');
+        });
+
+        it('should add a suffix to variants that collide', () => {
+            let mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
+            mockEnumDeclaration.getOwnProperties.returns(['ACTIVE', 'Active', 'active', 'self'].map(name => {
+                const mockEnumValueDeclaration = sinon.createStubInstance(EnumValueDeclaration);
+                mockEnumValueDeclaration.getName.returns(name);
+                return mockEnumValueDeclaration;
+            }));
+
+            [...rustVisitor.getEnumVariants(mockEnumDeclaration)].should.deep.equal([
+                ['ACTIVE', 'Active'],
+                ['Active', 'Active_'],
+                ['active', 'Active__'],
+                ['self', 'Self_'],
+            ]);
+        });
+
+        it('should reference the variant of an enum default', () => {
+            let mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
+            mockEnumDeclaration.getOwnProperties.returns(['PASSED_TESTING'].map(name => {
+                const mockEnumValueDeclaration = sinon.createStubInstance(EnumValueDeclaration);
+                mockEnumValueDeclaration.getName.returns(name);
+                return mockEnumValueDeclaration;
+            }));
+            let mockModelManager = sinon.createStubInstance(ModelManager);
+            mockModelManager.getType.withArgs('org.acme@1.0.0.Status').returns(mockEnumDeclaration);
+            let mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getModelManager.returns(mockModelManager);
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getModelFile.returns(mockModelFile);
+            const mockField = sinon.createStubInstance(Field);
+            mockField.isField.returns(true);
+            mockField.isTypeEnum.returns(true);
+            mockField.getType.returns('Status');
+            mockField.getFullyQualifiedTypeName.returns('org.acme@1.0.0.Status');
+            mockField.getDefaultValue.returns('PASSED_TESTING');
+            mockField.getParent.returns(mockClassDeclaration);
+            sinon.stub(rustVisitor, 'toTypeReference').returns('Status');
+
+            rustVisitor.toDefaultValue(mockField, {}).should.equal('Status::PassedTesting');
+        });
+    });
+
+    describe('getDerives', () => {
+        let mockClassDeclaration;
+        let mockName;
+        let mockHeight;
+        beforeEach(() => {
+            mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getFullyQualifiedName.returns('org.acme@1.0.0.Person');
+            mockName = sinon.createStubInstance(Field);
+            mockName.isPrimitive.returns(true);
+            mockName.getName.returns('name');
+            mockName.getType.returns('String');
+            mockHeight = sinon.createStubInstance(Field);
+            mockHeight.isPrimitive.returns(true);
+            mockHeight.getName.returns('height');
+            mockHeight.getType.returns('Double');
+            sinon.stub(rustVisitor, 'hasDefault').returns(false);
+        });
+
+        it('should derive Debug, Serialize and Deserialize by default', () => {
+            rustVisitor.getDerives(mockClassDeclaration, {}).should.deep.equal(['Debug', 'Serialize', 'Deserialize']);
+        });
+
+        it('should add the configured derives without duplicates', () => {
+            mockClassDeclaration.getProperties.returns([mockName]);
+            const parameters = { derives: { struct: ['Debug', 'Clone', 'PartialEq', 'Eq', 'std::hash::Hash', 'schemars::JsonSchema'] } };
+            rustVisitor.getDerives(mockClassDeclaration, parameters).should.deep.equal([
+                'Debug', 'Serialize', 'Deserialize', 'Clone', 'PartialEq', 'Eq', 'std::hash::Hash', 'schemars::JsonSchema'
+            ]);
+        });
+
+        it('should not derive Eq, Hash and Ord for a struct with a floating point field', () => {
+            mockClassDeclaration.getProperties.returns([mockName, mockHeight]);
+            const parameters = { derives: { struct: ['Clone', 'PartialEq', 'Eq', 'Hash', 'PartialOrd', 'Ord'] } };
+            rustVisitor.getDerives(mockClassDeclaration, parameters).should.deep.equal([
+                'Debug', 'Serialize', 'Deserialize', 'Clone', 'PartialEq', 'PartialOrd'
+            ]);
+        });
+
+        it('should derive Eq for a Double stored as an integer type', () => {
+            mockClassDeclaration.getProperties.returns([mockHeight]);
+            const parameters = { derives: { struct: ['PartialEq', 'Eq'] }, primitiveTypes: { Double: 'i64' } };
+            rustVisitor.getDerives(mockClassDeclaration, parameters).should.deep.equal(['Debug', 'Serialize', 'Deserialize', 'PartialEq', 'Eq']);
+        });
+
+        it('should not derive Deserialize or Eq for a validated Double scalar', () => {
+            let mockScalarDeclaration = sinon.createStubInstance(ScalarDeclaration);
+            mockScalarDeclaration.isScalarDeclaration.returns(true);
+            mockScalarDeclaration.getType.returns('Double');
+            mockScalarDeclaration.getValidator.returns({ getLowerBound: () => 0, getUpperBound: () => null });
+            const parameters = { derives: { scalar: ['Clone', 'Eq'] } };
+            rustVisitor.getDerives(mockScalarDeclaration, parameters).should.deep.equal(['Debug', 'Serialize', 'Clone']);
+        });
+
+        it('should not derive Copy for a struct with a String, an array or a relationship field', () => {
+            let mockAge = sinon.createStubInstance(Field);
+            mockAge.isPrimitive.returns(true);
+            mockAge.getName.returns('age');
+            mockAge.getType.returns('Integer');
+            let mockAges = sinon.createStubInstance(Field);
+            mockAges.isPrimitive.returns(true);
+            mockAges.isArray.returns(true);
+            mockAges.getName.returns('ages');
+            mockAges.getType.returns('Integer');
+            let mockManager = sinon.createStubInstance(RelationshipDeclaration);
+            mockManager.isRelationship.returns(true);
+            mockManager.getName.returns('manager');
+            const parameters = { derives: { struct: ['Clone', 'Copy', 'PartialEq'] } };
+
+            mockClassDeclaration.getProperties.returns([mockAge, mockHeight]);
+            rustVisitor.getDerives(mockClassDeclaration, parameters).should.deep.equal(['Debug', 'Serialize', 'Deserialize', 'Clone', 'Copy', 'PartialEq']);
+            [mockName, mockAges, mockManager].forEach(property => {
+                mockClassDeclaration.getProperties.returns([mockAge, property]);
+                rustVisitor.getDerives(mockClassDeclaration, parameters).should.deep.equal(['Debug', 'Serialize', 'Deserialize', 'Clone', 'PartialEq']);
+            });
+        });
+
+        it('should not derive the traits whose supertraits are not derived', () => {
+            mockClassDeclaration.getProperties.returns([mockName, mockHeight]);
+            const parameters = { derives: { struct: ['Copy', 'Eq', 'PartialOrd', 'Ord', 'Hash'] } };
+            rustVisitor.getDerives(mockClassDeclaration, parameters).should.deep.equal(['Debug', 'Serialize', 'Deserialize']);
+        });
+
+        it('should not derive the enum traits without their supertraits', () => {
+            let mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
+            mockEnumDeclaration.isEnum.returns(true);
+            const parameters = { derives: { enum: ['Copy', 'Hash'] } };
+            rustVisitor.getDerives(mockEnumDeclaration, parameters).should.deep.equal(['Debug', 'Serialize', 'Deserialize', 'Hash']);
+        });
+
+        it('should only derive the traits of a field typed by a declaration that it derives', () => {
+            let mockState = sinon.createStubInstance(Field);
+            mockState.isTypeEnum.returns(true);
+            mockState.getName.returns('state');
+            mockState.getType.returns('State');
+            mockState.getFullyQualifiedTypeName.returns('org.acme@1.0.0.State');
+            let mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
+            mockEnumDeclaration.isEnum.returns(true);
+            mockState.getParent.returns({ getModelFile: () => ({ getModelManager: () => ({ getType: () => mockEnumDeclaration }) }) });
+            sinon.stub(rustVisitor, 'getUnionDeclaration').returns(null);
+            sinon.stub(rustVisitor, 'isFieldRecursive').returns(false);
+            mockClassDeclaration.getProperties.returns([mockState]);
+            const parameters = { derives: { struct: ['Clone', 'PartialEq', 'Eq', 'Hash'], enum: ['Clone', 'Copy'] } };
+            rustVisitor.getDerives(mockClassDeclaration, parameters).should.deep.equal(['Debug', 'Serialize', 'Deserialize', 'Clone']);
+        });
+
+        it('should derive the struct traits that all variants of a union derive', () => {
+            let mockLaptop = sinon.createStubInstance(ClassDeclaration);
+            mockLaptop.getProperties.returns([mockName]);
+            let mockTablet = sinon.createStubInstance(ClassDeclaration);
+            mockTablet.getProperties.returns([mockHeight]);
+            mockClassDeclaration.isAbstract.returns(true);
+            mockClassDeclaration.getAssignableClassDeclarations.returns([mockClassDeclaration, mockLaptop, mockTablet]);
+            const parameters = { derives: { struct: ['Clone', 'Eq', 'Default'] } };
+            rustVisitor.getUnionDerives(mockClassDeclaration, parameters).should.deep.equal(['Debug', 'Deserialize', 'Clone']);
+        });
+    });
+
+    describe('visitClassDeclaration', () => {
+        let param;
+        beforeEach(() => {
+            param = {
+                fileWriter: mockFileWriter
+            };
+        });
+        it('should write the struct opening and close', () => {
+            let acceptSpy = sinon.spy();
+
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.isClassDeclaration.returns(true);
+            mockClassDeclaration.getProperties.returns([{
+                getName: () => 'name',
+                accept: acceptSpy
+            },
+            {
+                getName: () => '$identifier',
+                accept: acceptSpy
+            }]);
+            mockClassDeclaration.getName.returns('Bob');
+            let mockWriteClassValidation = sinon.stub(rustVisitor, 'writeClassValidation');
+            let mockWriteClassTrait = sinon.stub(rustVisitor, 'writeClassTrait');
+            let mockWriteTraitImplementations = sinon.stub(rustVisitor, 'writeTraitImplementations');
+            let mockWriteClassName = sinon.stub(rustVisitor, 'writeClassName');
+            let mockWriteClassUnion = sinon.stub(rustVisitor, 'writeClassUnion');
+            let mockWriteIdentifiable = sinon.stub(rustVisitor, 'writeIdentifiable');
+            let mockWriteConstructors = sinon.stub(rustVisitor, 'writeConstructors');
+            let mockWriteFieldDefaults = sinon.stub(rustVisitor, 'writeFieldDefaults');
+            let mockWriteDefault = sinon.stub(rustVisitor, 'writeDefault');
+            sinon.stub(rustVisitor, 'hasDefault').returns(false);
+
+            rustVisitor.visitClassDeclaration(mockClassDeclaration, param);
+            mockWriteClassName.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteConstructors.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteFieldDefaults.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteDefault.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteIdentifiable.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteClassValidation.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteClassTrait.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteTraitImplementations.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteClassUnion.calledWith(mockClassDeclaration, param).should.be.ok;
+            param.fileWriter.writeLine.callCount.should.deep.equal(11);
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [
+                    0, '#[derive(Debug, Serialize, Deserialize)]'
+                ],
+                [
+                    0, 'pub struct Bob {'
+                ],
+                [
+                    1, '#[serde('
+                ],
+                [
+                    2, 'rename = "$class",'
+                ],
+                [
+                    2, 'default,'
+                ],
+                [
+                    1, ')]'
+                ],
+                [
+                    1, 'pub _class: crate::lib::utils::Class<Bob>,'
+                ],
+                [
+                    1, ''
+                ],
+                [
+                    1, ''
+                ],
+                [
+                    0, '}'
+                ],
+                [
+                    0, ''
+                ]
+            ]);
+        });
+    });
+
+
+    describe('traits', () => {
+        let param;
+        let mockPerson;
+        let mockEmployee;
+        let mockAddress;
+        beforeEach(() => {
+            param = {
+                fileWriter: mockFileWriter
+            };
+            const mockEmail = sinon.createStubInstance(Field);
+            mockEmail.getName.returns('email');
+            mockEmail.getType.returns('String');
+            const mockMiddleName = sinon.createStubInstance(Field);
+            mockMiddleName.getName.returns('middleName');
+            mockMiddleName.getType.returns('String');
+            mockMiddleName.isOptional.returns(true);
+            const mockSalary = sinon.createStubInstance(Field);
+            mockSalary.getName.returns('salary');
+            mockSalary.getType.returns('Long');
+
+            mockPerson = sinon.createStubInstance(ClassDeclaration);
+            mockEmployee = sinon.createStubInstance(ClassDeclaration);
+            mockAddress = sinon.createStubInstance(ClassDeclaration);
+
+            mockPerson.getName.returns('Person');
+            mockPerson.isAbstract.returns(true);
+            mockPerson.getDirectSubclasses.returns([mockEmployee]);
+            mockPerson.getSuperTypeDeclaration.returns(null);
+            mockPerson.getOwnProperties.returns([mockEmail, mockMiddleName]);
+            mockPerson.getProperties.returns([mockEmail, mockMiddleName]);
+
+            mockEmployee.getName.returns('Employee');
+            mockEmployee.isAbstract.returns(false);
+            mockEmployee.getDirectSubclasses.returns([]);
+            mockEmployee.getSuperTypeDeclaration.returns(mockPerson);
+            mockEmployee.getOwnProperties.returns([mockSalary]);
+            mockEmployee.getProperties.returns([mockEmail, mockMiddleName, mockSalary]);
+
+            mockAddress.getName.returns('Address');
+            mockAddress.isAbstract.returns(false);
+            mockAddress.getDirectSubclasses.returns([]);
+            mockAddress.getSuperTypeDeclaration.returns(null);
+            mockAddress.getOwnProperties.returns([]);
+            mockAddress.getProperties.returns([]);
+        });
+
+        it('should only have traits for abstract classes and classes with subclasses', () => {
+            rustVisitor.hasTrait(mockPerson).should.equal(true);
+            rustVisitor.hasTrait(mockEmployee).should.equal(false);
+            mockEmployee.getDirectSubclasses.returns([mockAddress]);
+            rustVisitor.hasTrait(mockEmployee).should.equal(true);
+        });
+
+        it('should prefix trait names with I', () => {
+            rustVisitor.toTraitName(mockPerson).should.equal('IPerson');
+        });
+
+        it('should write a trait with an accessor for each own field', () => {
+            rustVisitor.writeClassTrait(mockPerson, param);
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'pub trait IPerson {'],
+                [1, 'fn email(&self) -> &String;'],
+                [1, 'fn middle_name(&self) -> &Option<String>;'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should write the trait of the super type as a super trait', () => {
+            mockEmployee.getDirectSubclasses.returns([mockAddress]);
+            mockEmployee.getOwnProperties.returns([]);
+            rustVisitor.writeClassTrait(mockEmployee, param);
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'pub trait IEmployee: IPerson {}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should not write a trait for a concrete class without subclasses', () => {
+            rustVisitor.writeClassTrait(mockAddress, param);
+            param.fileWriter.writeLine.callCount.should.equal(0);
+        });
+
+        it('should implement the traits of all super types', () => {
+            rustVisitor.writeTraitImplementations(mockEmployee, param);
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'impl IPerson for Employee {'],
+                [1, 'fn email(&self) -> &String {'],
+                [2, '&self.email'],
+                [1, '}'],
+                [0, ''],
+                [1, 'fn middle_name(&self) -> &Option<String> {'],
+                [2, '&self.middle_name'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should implement the trait of the class itself', () => {
+            mockEmployee.getDirectSubclasses.returns([mockAddress]);
+            mockEmployee.getOwnProperties.returns([]);
+            rustVisitor.writeTraitImplementations(mockEmployee, param);
+            param.fileWriter.writeLine.getCalls().map(call => call.args).slice(0, 2).should.deep.equal([
+                [0, 'impl IEmployee for Employee {}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should return the identifier field from the $identifier accessor of an explicitly identified class', () => {
+            const mockIdentifier = sinon.createStubInstance(Field);
+            mockIdentifier.getName.returns('$identifier');
+            mockIdentifier.getType.returns('String');
+            mockPerson.getOwnProperties.returns([mockIdentifier]);
+            mockPerson.getProperties.returns([mockIdentifier]);
+            mockEmployee.getOwnProperties.returns([]);
+            mockEmployee.getProperties.returns([mockIdentifier]);
+            mockEmployee.isExplicitlyIdentified.returns(true);
+            mockEmployee.getIdentifierFieldName.returns('employeeId');
+            rustVisitor.writeTraitImplementations(mockEmployee, param);
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'impl IPerson for Employee {'],
+                [1, 'fn _identifier(&self) -> &String {'],
+                [2, '&self.employee_id'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should not implement any trait for a class without super types', () => {
+            rustVisitor.writeTraitImplementations(mockAddress, param);
+            param.fileWriter.writeLine.callCount.should.equal(0);
+        });
+    });
+
+    describe('visitClassDeclaration for abstract classes', () => {
+        it('should not write a struct for an abstract class', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.isClassDeclaration.returns(true);
+            mockClassDeclaration.isAbstract.returns(true);
+            mockClassDeclaration.getName.returns('Equipment');
+            let mockWriteClassValidation = sinon.stub(rustVisitor, 'writeClassValidation');
+            let mockWriteClassTrait = sinon.stub(rustVisitor, 'writeClassTrait');
+            let mockWriteClassUnion = sinon.stub(rustVisitor, 'writeClassUnion');
+            let mockWriteClassName = sinon.stub(rustVisitor, 'writeClassName');
+            let mockWriteTraitImplementations = sinon.stub(rustVisitor, 'writeTraitImplementations');
+            let mockWriteFieldDefaults = sinon.stub(rustVisitor, 'writeFieldDefaults');
+
+            rustVisitor.visitClassDeclaration(mockClassDeclaration, param);
+
+            param.fileWriter.writeLine.callCount.should.equal(0);
+            mockWriteFieldDefaults.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteClassValidation.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteClassTrait.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteClassUnion.calledWith(mockClassDeclaration, param).should.be.ok;
+            mockWriteClassName.called.should.equal(false);
+            mockWriteTraitImplementations.called.should.equal(false);
+        });
+
+        it('should only write the field validation functions of an abstract class', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.isAbstract.returns(true);
+            mockClassDeclaration.getName.returns('Equipment');
+            mockClassDeclaration.getProperties.returns([]);
+            mockClassDeclaration.getOwnProperties.returns([]);
+
+            rustVisitor.writeClassValidation(mockClassDeclaration, param);
+
+            param.fileWriter.writeLine.callCount.should.equal(0);
+        });
+    });
+
+    describe('writeIdentifiable', () => {
+        let param;
+        let mockClassDeclaration;
+        let mockIdentifier;
+        beforeEach(() => {
+            param = {
+                fileWriter: mockFileWriter
+            };
+            mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockIdentifier = sinon.createStubInstance(Field);
+            mockIdentifier.getName.returns('serialNumber');
+            mockIdentifier.isTypeScalar.returns(false);
+            mockClassDeclaration.getName.returns('Laptop');
+            mockClassDeclaration.isIdentified.returns(true);
+            mockClassDeclaration.getIdentifierFieldName.returns('serialNumber');
+            mockClassDeclaration.getProperties.returns([mockIdentifier]);
+        });
+
+        it('should implement Identifiable for an identified class', () => {
+            rustVisitor.writeIdentifiable(mockClassDeclaration, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'impl Identifiable for Laptop {'],
+                [1, 'fn fully_qualified_type_name(&self) -> &\'static str {'],
+                [2, 'Self::CLASS'],
+                [1, '}'],
+                [0, ''],
+                [1, 'fn identifier(&self) -> &str {'],
+                [2, '&self.serial_number'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should return the value of an identifier typed by a scalar', () => {
+            mockIdentifier.isTypeScalar.returns(true);
+            rustVisitor.writeIdentifiable(mockClassDeclaration, param);
+            param.fileWriter.writeLine.withArgs(2, '&self.serial_number.0').calledOnce.should.be.ok;
+        });
+
+        it('should return the system identifier of a system identified class', () => {
+            mockIdentifier.getName.returns('$identifier');
+            mockClassDeclaration.getIdentifierFieldName.returns('$identifier');
+            rustVisitor.writeIdentifiable(mockClassDeclaration, param);
+            param.fileWriter.writeLine.withArgs(2, '&self._identifier').calledOnce.should.be.ok;
+        });
+
+        it('should not implement Identifiable for a class without identifier', () => {
+            mockClassDeclaration.isIdentified.returns(false);
+            rustVisitor.writeIdentifiable(mockClassDeclaration, param);
+            param.fileWriter.writeLine.callCount.should.equal(0);
+        });
+    });
+
+    describe('writeClassName', () => {
+        it('should write the fully qualified name of the class', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getFullyQualifiedName.returns('org.acme@1.0.0.Bob');
+
+            rustVisitor.writeClassName(mockClassDeclaration, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'impl ConcertoClass for Bob {'],
+                [1, 'const CLASS: &\'static str = "org.acme@1.0.0.Bob";'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+    });
+
+    describe('writeConstructors', () => {
+        it('should write a new function for the required fields and a builder for the optional fields', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            const mockTimestamp = sinon.createStubInstance(Field);
+            mockTimestamp.getName.returns('$timestamp');
+            mockTimestamp.getType.returns('DateTime');
+            const mockName = sinon.createStubInstance(Field);
+            mockName.getName.returns('name');
+            mockName.getType.returns('String');
+            const mockNickname = sinon.createStubInstance(Field);
+            mockNickname.getName.returns('nickName');
+            mockNickname.getType.returns('String');
+            mockNickname.isOptional.returns(true);
+            const mockScores = sinon.createStubInstance(Field);
+            mockScores.getName.returns('scores');
+            mockScores.getType.returns('Double');
+            mockScores.isArray.returns(true);
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getProperties.returns([mockTimestamp, mockName, mockNickname, mockScores]);
+
+            rustVisitor.writeConstructors(mockClassDeclaration, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'impl Bob {'],
+                [1, '/// Creates the struct from its required fields.'],
+                [1, 'pub fn new(name: String, scores: Vec<f64>) -> Self {'],
+                [2, 'Self {'],
+                [3, '_class: Default::default(),'],
+                [3, '_timestamp: default_timestamp(),'],
+                [3, 'name,'],
+                [3, 'nick_name: None,'],
+                [3, 'scores,'],
+                [2, '}'],
+                [1, '}'],
+                [0, ''],
+                [1, '/// Returns a builder of the struct from its required fields, to set its optional fields.'],
+                [1, 'pub fn builder(name: String, scores: Vec<f64>) -> BobBuilder {'],
+                [2, 'BobBuilder { value: Self::new(name, scores) }'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+                [0, '/// Builder of `Bob`, which checks the Concerto validators of its fields.'],
+                [0, '#[derive(Debug)]'],
+                [0, 'pub struct BobBuilder {'],
+                [1, 'value: Bob,'],
+                [0, '}'],
+                [0, ''],
+                [0, 'impl BobBuilder {'],
+                [1, '/// Sets the optional `nickName` field.'],
+                [1, 'pub fn nick_name(mut self, nick_name: String) -> Self {'],
+                [2, 'self.value.nick_name = Some(nick_name);'],
+                [2, 'self'],
+                [1, '}'],
+                [0, ''],
+                [1, '/// Returns the built `Bob`, or the first validation error of its fields.'],
+                [1, 'pub fn build(self) -> std::result::Result<Bob, ValidationError> {'],
+                [2, 'self.value.validate()?;'],
+                [2, 'Ok(self.value)'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should rename the setter of an optional field named build', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            const mockBuild = sinon.createStubInstance(Field);
+            mockBuild.getName.returns('build');
+            mockBuild.getType.returns('String');
+            mockBuild.isOptional.returns(true);
+            const mockWithBuild = sinon.createStubInstance(Field);
+            mockWithBuild.getName.returns('with_build');
+            mockWithBuild.getType.returns('String');
+            mockWithBuild.isOptional.returns(true);
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getProperties.returns([mockBuild, mockWithBuild]);
+
+            rustVisitor.writeConstructors(mockClassDeclaration, param);
+
+            const lines = param.fileWriter.writeLine.getCalls().map(call => call.args[1]);
+            lines.should.include('pub fn with_with_build(mut self, build: String) -> Self {');
+            lines.should.include('self.value.build = Some(build);');
+            lines.should.include('pub fn with_build(mut self, with_build: String) -> Self {');
+            lines.filter(line => line.startsWith('pub fn build(')).should.have.length(1);
+        });
+
+        it('should allow new functions with many arguments', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            const fields = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'].map(name => {
+                const mockField = sinon.createStubInstance(Field);
+                mockField.getName.returns(name);
+                mockField.getType.returns('Boolean');
+                return mockField;
+            });
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getProperties.returns(fields);
+
+            rustVisitor.writeConstructors(mockClassDeclaration, param);
+
+            param.fileWriter.writeLine.withArgs(1, '#[allow(clippy::too_many_arguments)]').calledTwice.should.be.ok;
+        });
+    });
+
+    describe('writeFieldDefaults', () => {
+        it('should write a function returning the modelled default of each field', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Bob');
+            const createField = (name, type, defaultValue, optional) => {
+                const mockField = sinon.createStubInstance(Field);
+                mockField.isField.returns(true);
+                mockField.isPrimitive.returns(true);
+                mockField.getName.returns(name);
+                mockField.getType.returns(type);
+                mockField.getDefaultValue.returns(defaultValue);
+                mockField.isOptional.returns(optional);
+                mockField.getParent.returns(mockClassDeclaration);
+                return mockField;
+            };
+            mockClassDeclaration.getOwnProperties.returns([
+                createField('city', 'String', 'Winchester', false),
+                createField('count', 'Integer', 10, true),
+                createField('ratio', 'Double', 999, false),
+                createField('since', 'DateTime', '2008-09-15T15:53:00', true),
+                createField('nickName', 'String', undefined, false),
+            ]);
+
+            rustVisitor.writeFieldDefaults(mockClassDeclaration, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'pub fn default_bob_city() -> String {'],
+                [1, 'String::from(r#"Winchester"#)'],
+                [0, '}'],
+                [0, ''],
+                [0, 'pub fn default_bob_count() -> Option<i32> {'],
+                [1, 'Some(10)'],
+                [0, '}'],
+                [0, ''],
+                [0, 'pub fn default_bob_ratio() -> f64 {'],
+                [1, '999.0'],
+                [0, '}'],
+                [0, ''],
+                [0, 'pub fn default_bob_since() -> Option<DateTime<Utc>> {'],
+                [1, 'Some(Utc.timestamp_millis_opt(1221493980000).unwrap())'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+    });
+
+    describe('toRustLiteral', () => {
+        it('should convert the default values of primitives', () => {
+            rustVisitor.toRustLiteral('String', '000-00-0000').should.equal('String::from(r#"000-00-0000"#)');
+            rustVisitor.toRustLiteral('Boolean', false).should.equal('false');
+            rustVisitor.toRustLiteral('Long', 1000).should.equal('1000');
+            rustVisitor.toRustLiteral('Double', 1).should.equal('1.0');
+        });
+
+        it('should keep the offset of a DateTime default', () => {
+            rustVisitor.toRustLiteral('DateTime', '2008-09-15T15:53:00+01:00').should.equal('Utc.timestamp_millis_opt(1221490380000).unwrap()');
+        });
+
+        it('should convert a DateTime default for each time library', () => {
+            rustVisitor.toRustLiteral('DateTime', '2008-09-15T15:53:00+01:00', { timeLibrary: 'chrono-fixed-offset' })
+                .should.equal('FixedOffset::east_opt(3600).unwrap().timestamp_millis_opt(1221490380000).unwrap()');
+            rustVisitor.toRustLiteral('DateTime', '2008-09-15T15:53:00-0530', { timeLibrary: 'chrono-fixed-offset' })
+                .should.equal('FixedOffset::east_opt(-19800).unwrap().timestamp_millis_opt(1221513780000).unwrap()');
+            rustVisitor.toRustLiteral('DateTime', '2008-09-15T15:53:00', { timeLibrary: 'chrono-fixed-offset' })
+                .should.equal('FixedOffset::east_opt(0).unwrap().timestamp_millis_opt(1221493980000).unwrap()');
+            rustVisitor.toRustLiteral('DateTime', '2008-09-15T15:53:00Z', { timeLibrary: 'time' })
+                .should.equal('OffsetDateTime::from_unix_timestamp_nanos(1221493980000 * 1_000_000).unwrap()');
+        });
+
+        it('should return null without a valid default', () => {
+            (rustVisitor.toRustLiteral('String', undefined) === null).should.be.ok;
+            (rustVisitor.toRustLiteral('DateTime', 'yesterday') === null).should.be.ok;
+        });
+    });
+
+    describe('writeDefault', () => {
+        let param;
+        let mockClassDeclaration;
+        let mockName;
+        beforeEach(() => {
+            param = {
+                fileWriter: mockFileWriter
+            };
+            mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getFullyQualifiedName.returns('org.acme@1.0.0.Bob');
+            const mockTimestamp = sinon.createStubInstance(Field);
+            mockTimestamp.isPrimitive.returns(true);
+            mockTimestamp.getName.returns('$timestamp');
+            mockTimestamp.getType.returns('DateTime');
+            mockName = sinon.createStubInstance(Field);
+            mockName.isField.returns(true);
+            mockName.isPrimitive.returns(true);
+            mockName.getName.returns('name');
+            mockName.getType.returns('String');
+            mockName.getDefaultValue.returns('Bob');
+            mockName.getParent.returns(mockClassDeclaration);
+            const mockAge = sinon.createStubInstance(Field);
+            mockAge.isField.returns(true);
+            mockAge.isPrimitive.returns(true);
+            mockAge.getName.returns('age');
+            mockAge.getType.returns('Integer');
+            mockClassDeclaration.getProperties.returns([mockTimestamp, mockName, mockAge]);
+        });
+
+        it('should use the modelled defaults, or the defaults of the Rust types', () => {
+            rustVisitor.writeDefault(mockClassDeclaration, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'impl Default for Bob {'],
+                [1, 'fn default() -> Self {'],
+                [2, 'Self {'],
+                [3, '_class: Default::default(),'],
+                [3, '_timestamp: default_timestamp(),'],
+                [3, 'name: default_bob_name(),'],
+                [3, 'age: Default::default(),'],
+                [2, '}'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should derive Default rather than implement it when no field has a modelled default', () => {
+            mockName.getDefaultValue.returns(undefined);
+            mockClassDeclaration.getProperties.returns([mockName]);
+
+            rustVisitor.writeDefault(mockClassDeclaration, param);
+
+            param.fileWriter.writeLine.callCount.should.equal(0);
+            rustVisitor.getDerives(mockClassDeclaration, param).should.deep.equal(['Debug', 'Serialize', 'Deserialize', 'Default']);
+        });
+
+        it('should not derive a configured Default when it implements Default', () => {
+            const parameters = Object.assign(param, { derives: { struct: ['Clone', 'std::default::Default'] } });
+            rustVisitor.getDerives(mockClassDeclaration, parameters).should.deep.equal(['Debug', 'Serialize', 'Deserialize', 'Clone']);
+        });
+
+        it('should not implement Default when an enum field has no default', () => {
+            const mockState = sinon.createStubInstance(Field);
+            mockState.isField.returns(true);
+            mockState.isTypeEnum.returns(true);
+            mockState.getName.returns('state');
+            mockState.getType.returns('State');
+            mockClassDeclaration.getProperties.returns([mockName, mockState]);
+
+            rustVisitor.writeDefault(mockClassDeclaration, param);
+
+            param.fileWriter.writeLine.callCount.should.equal(0);
+        });
+
+        it('should not implement Default for a DateTime field without a default in the time library', () => {
+            const mockSince = sinon.createStubInstance(Field);
+            mockSince.isField.returns(true);
+            mockSince.isPrimitive.returns(true);
+            mockSince.getName.returns('since');
+            mockSince.getType.returns('DateTime');
+            mockClassDeclaration.getProperties.returns([mockName, mockSince]);
+
+            rustVisitor.writeDefault(mockClassDeclaration, Object.assign(param, { timeLibrary: 'time' }));
+
+            param.fileWriter.writeLine.callCount.should.equal(0);
+            rustVisitor.hasDefault(mockClassDeclaration, { timeLibrary: 'chrono' }).should.be.true;
+        });
+
+        it('should not implement Default for a class with a required relationship', () => {
+            const mockOwner = sinon.createStubInstance(RelationshipDeclaration);
+            mockOwner.isRelationship.returns(true);
+            mockOwner.getName.returns('owner');
+            mockOwner.getType.returns('Person');
+            mockClassDeclaration.getProperties.returns([mockName, mockOwner]);
+
+            rustVisitor.writeDefault(mockClassDeclaration, param);
+
+            param.fileWriter.writeLine.callCount.should.equal(0);
+        });
+    });
+
+    describe('unions', () => {
+        let param;
+        let mockEquipment;
+        let mockLaptop;
+        let mockOtherLaptop;
+        let mockAddress;
+        beforeEach(() => {
+            param = {
+                fileWriter: mockFileWriter
+            };
+            mockEquipment = sinon.createStubInstance(ClassDeclaration);
+            mockLaptop = sinon.createStubInstance(ClassDeclaration);
+            mockOtherLaptop = sinon.createStubInstance(ClassDeclaration);
+            mockAddress = sinon.createStubInstance(ClassDeclaration);
+
+            mockEquipment.isClassDeclaration.returns(true);
+            mockEquipment.getName.returns('Equipment');
+            mockEquipment.getNamespace.returns('org.acme@1.0.0');
+            mockEquipment.getFullyQualifiedName.returns('org.acme@1.0.0.Equipment');
+            mockEquipment.isAbstract.returns(true);
+            mockEquipment.getDirectSubclasses.returns([mockLaptop]);
+            mockEquipment.getAssignableClassDeclarations.returns([mockEquipment, mockLaptop]);
+
+            mockLaptop.getName.returns('Laptop');
+            mockLaptop.getNamespace.returns('org.acme@1.0.0');
+            mockLaptop.getFullyQualifiedName.returns('org.acme@1.0.0.Laptop');
+            mockLaptop.isAbstract.returns(false);
+
+            mockOtherLaptop.getName.returns('Laptop');
+            mockOtherLaptop.getNamespace.returns('org.other@1.0.0');
+            mockOtherLaptop.getFullyQualifiedName.returns('org.other@1.0.0.Laptop');
+            mockOtherLaptop.isAbstract.returns(false);
+
+            mockAddress.isClassDeclaration.returns(true);
+            mockAddress.isAbstract.returns(false);
+            mockAddress.getDirectSubclasses.returns([]);
+            mockAddress.getAssignableClassDeclarations.returns([mockAddress]);
+        });
+
+        it('should return the concrete assignable classes', () => {
+            rustVisitor.getConcreteClassDeclarations(mockEquipment).should.deep.equal([mockLaptop]);
+        });
+
+        it('should not return the enums that extend a class', () => {
+            const mockState = sinon.createStubInstance(EnumDeclaration);
+            mockState.isEnum.returns(true);
+            mockState.isAbstract.returns(false);
+            mockEquipment.getAssignableClassDeclarations.returns([mockEquipment, mockLaptop, mockState]);
+            rustVisitor.getConcreteClassDeclarations(mockEquipment).should.deep.equal([mockLaptop]);
+        });
+
+        it('should only have unions for classes with traits and concrete subclasses', () => {
+            rustVisitor.hasUnion(mockEquipment).should.equal(true);
+            rustVisitor.hasUnion(mockAddress).should.equal(false);
+            mockEquipment.getAssignableClassDeclarations.returns([mockEquipment]);
+            rustVisitor.hasUnion(mockEquipment).should.equal(true);
+        });
+
+        it('should suffix union names with Union', () => {
+            rustVisitor.toUnionName(mockEquipment).should.equal('EquipmentUnion');
+        });
+
+        it('should return the union declaration of a field typed by a super type', () => {
+            const mockModelManager = sinon.createStubInstance(ModelManager);
+            const mockModelFile = sinon.createStubInstance(ModelFile);
+            const mockField = sinon.createStubInstance(Field);
+            mockModelManager.getType.withArgs('org.acme@1.0.0.Equipment').returns(mockEquipment);
+            mockModelManager.getType.withArgs('org.acme@1.0.0.Address').returns(mockAddress);
+            mockModelFile.getModelManager.returns(mockModelManager);
+            mockAddress.getModelFile.returns(mockModelFile);
+            mockField.getParent.returns(mockAddress);
+            mockField.isPrimitive.returns(false);
+
+            mockField.getFullyQualifiedTypeName.returns('org.acme@1.0.0.Equipment');
+            rustVisitor.getUnionDeclaration(mockField).should.equal(mockEquipment);
+            mockField.getFullyQualifiedTypeName.returns('org.acme@1.0.0.Address');
+            (rustVisitor.getUnionDeclaration(mockField) === null).should.be.ok;
+            mockField.isPrimitive.returns(true);
+            (rustVisitor.getUnionDeclaration(mockField) === null).should.be.ok;
+        });
+
+        it('should use the union as the type of a field typed by a super type', () => {
+            const mockField = sinon.createStubInstance(Field);
+            mockField.isPrimitive.returns(false);
+            mockField.isArray.returns(true);
+            mockField.name = 'assets';
+            mockField.type = 'Equipment';
+            sinon.stub(rustVisitor, 'getUnionDeclaration').returns(mockEquipment);
+
+            rustVisitor.visitField(mockField, param);
+
+            param.fileWriter.writeLine.withArgs(1, 'pub assets: Vec<EquipmentUnion>,').calledOnce.should.be.ok;
+        });
+
+        it('should write a $class tagged enum with a variant per concrete class', () => {
+            rustVisitor.writeClassUnion(mockEquipment, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, '#[derive(Debug, Deserialize)]'],
+                [0, '#[serde(tag = "$class")]'],
+                [0, 'pub enum EquipmentUnion {'],
+                [1, '#[serde(rename = "org.acme@1.0.0.Laptop")]'],
+                [1, 'Laptop(Laptop),'],
+                [0, '}'],
+                [0, ''],
+                [0, 'impl ConcertoClass for EquipmentUnion {'],
+                [1, 'const CLASS: &\'static str = "org.acme@1.0.0.Equipment";'],
+                [1, 'const CLASSES: &\'static [&\'static str] = &['],
+                [2, '"org.acme@1.0.0.Laptop",'],
+                [1, '];'],
+                [0, '}'],
+                [0, ''],
+                [0, 'impl EquipmentUnion {'],
+                [1, '/// Returns the variant as a `dyn IEquipment` trait object.'],
+                [1, 'pub fn as_dyn(&self) -> &dyn IEquipment {'],
+                [2, 'match self {'],
+                [3, 'EquipmentUnion::Laptop(value) => value,'],
+                [2, '}'],
+                [1, '}'],
+                [0, ''],
+                [1, '/// Checks the Concerto validators of the variant.'],
+                [1, 'pub fn validate(&self) -> std::result::Result<(), ValidationError> {'],
+                [2, 'match self {'],
+                [3, 'EquipmentUnion::Laptop(value) => value.validate(),'],
+                [2, '}'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+                [0, 'impl Serialize for EquipmentUnion {'],
+                [1, 'fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>'],
+                [1, 'where'],
+                [2, 'S: serde::Serializer,'],
+                [1, '{'],
+                [2, 'match self {'],
+                [3, 'EquipmentUnion::Laptop(value) => value.serialize(serializer),'],
+                [2, '}'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should implement Identifiable for the union of an identified class', () => {
+            mockEquipment.isIdentified.returns(true);
+            rustVisitor.writeClassUnion(mockEquipment, param);
+
+            param.fileWriter.writeLine.withArgs(0, 'impl Identifiable for EquipmentUnion {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(3, 'EquipmentUnion::Laptop(value) => value.fully_qualified_type_name(),').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(3, 'EquipmentUnion::Laptop(value) => value.identifier(),').calledOnce.should.be.ok;
+        });
+
+        it('should qualify variants from other namespaces and disambiguate their names', () => {
+            mockEquipment.getAssignableClassDeclarations.returns([mockEquipment, mockLaptop, mockOtherLaptop]);
+            rustVisitor.writeClassUnion(mockEquipment, param);
+
+            param.fileWriter.writeLine.withArgs(1, 'LaptopOrgAcme100(Laptop),').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(1, 'LaptopOrgOther100(crate::lib::org_other_1_0_0::Laptop),').calledOnce.should.be.ok;
+        });
+
+        it('should write an empty union for an abstract class without concrete subclasses', () => {
+            mockEquipment.getAssignableClassDeclarations.returns([mockEquipment]);
+            rustVisitor.writeClassUnion(mockEquipment, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).slice(0, 24).should.deep.equal([
+                [0, '#[derive(Debug, Deserialize)]'],
+                [0, 'pub enum EquipmentUnion {'],
+                [0, '}'],
+                [0, ''],
+                [0, 'impl ConcertoClass for EquipmentUnion {'],
+                [1, 'const CLASS: &\'static str = "org.acme@1.0.0.Equipment";'],
+                [1, 'const CLASSES: &\'static [&\'static str] = &['],
+                [1, '];'],
+                [0, '}'],
+                [0, ''],
+                [0, 'impl EquipmentUnion {'],
+                [1, '/// Returns the variant as a `dyn IEquipment` trait object.'],
+                [1, 'pub fn as_dyn(&self) -> &dyn IEquipment {'],
+                [2, 'match *self {'],
+                [2, '}'],
+                [1, '}'],
+                [0, ''],
+                [1, '/// Checks the Concerto validators of the variant.'],
+                [1, 'pub fn validate(&self) -> std::result::Result<(), ValidationError> {'],
+                [2, 'match *self {'],
+                [2, '}'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should only have unions for the system types that a property refers to', () => {
+            const mockModelFile = sinon.createStubInstance(ModelFile);
+            const mockOtherModelFile = sinon.createStubInstance(ModelFile);
+            const mockModelManager = sinon.createStubInstance(ModelManager);
+            const mockField = sinon.createStubInstance(Field);
+            mockEquipment.getModelFile.returns(mockModelFile);
+            mockModelFile.isSystemModelFile.returns(true);
+            mockModelFile.getModelManager.returns(mockModelManager);
+            mockModelManager.getModelFiles.returns([mockModelFile, mockOtherModelFile]);
+            mockModelFile.getAllDeclarations.returns([mockEquipment]);
+            mockEquipment.getOwnProperties.returns([]);
+            mockOtherModelFile.getAllDeclarations.returns([mockAddress]);
+            mockAddress.getOwnProperties.returns([mockField]);
+            mockField.isPrimitive.returns(false);
+            mockField.getFullyQualifiedTypeName.returns('org.acme@1.0.0.Laptop');
+
+            rustVisitor.hasUnion(mockEquipment).should.equal(false);
+            mockField.getFullyQualifiedTypeName.returns('org.acme@1.0.0.Equipment');
+            rustVisitor.hasUnion(mockEquipment).should.equal(true);
+        });
+
+        it('should not write a union for a class without subclasses', () => {
+            rustVisitor.writeClassUnion(mockAddress, param);
+            param.fileWriter.writeLine.callCount.should.equal(0);
+        });
+    });
+
+    describe('isFieldRecursive', () => {
+        let mockField;
+        beforeEach(() => {
+            const mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getFullyQualifiedName.returns('org.acme@1.0.0.Person');
+            mockClassDeclaration.getAssignableClassDeclarations.returns([mockClassDeclaration]);
+            mockField = sinon.createStubInstance(Field);
+            mockField.getParent.returns(mockClassDeclaration);
+            mockField.isPrimitive.returns(false);
+            mockField.isArray.returns(false);
+            mockField.isTypeScalar.returns(false);
+        });
+
+        it('should not box primitive, scalar or array fields', () => {
+            mockField.accept.returns(true);
+            mockField.isPrimitive.returns(true);
+            rustVisitor.isFieldRecursive(mockField).should.equal(false);
+            mockField.isPrimitive.returns(false);
+            mockField.isTypeScalar.returns(true);
+            rustVisitor.isFieldRecursive(mockField).should.equal(false);
+            mockField.isTypeScalar.returns(false);
+            mockField.isArray.returns(true);
+            rustVisitor.isFieldRecursive(mockField).should.equal(false);
+            mockField.accept.called.should.equal(false);
+        });
+
+        it('should detect recursion starting from the class of the field', () => {
+            mockField.accept.returns(true);
+            rustVisitor.isFieldRecursive(mockField).should.equal(true);
+            mockField.accept.firstCall.args[1].should.deep.equal({ stack: ['org.acme@1.0.0.Person'], roots: ['org.acme@1.0.0.Person'] });
+            mockField.accept.returns(false);
+            rustVisitor.isFieldRecursive(mockField).should.equal(false);
+        });
+
+        it('should only box the fields that lead back to their own class', () => {
+            const modelManager = new ModelManager();
+            modelManager.addCTOModel(`namespace org.acme@1.0.0
+            concept A {
+                o B b
+            }
+            concept B {
+                o B child optional
+                o C c optional
+            }
+            concept C {
+                o B b optional
+            }`, 'test.cto');
+            const field = (type, name) => modelManager.getType(`org.acme@1.0.0.${type}`).getProperty(name);
+            rustVisitor.isFieldRecursive(field('A', 'b')).should.equal(false);
+            rustVisitor.isFieldRecursive(field('B', 'child')).should.equal(true);
+            rustVisitor.isFieldRecursive(field('B', 'c')).should.equal(true);
+            rustVisitor.isFieldRecursive(field('C', 'b')).should.equal(true);
+        });
+
+        it('should box the fields that lead back to a subclass of their class', () => {
+            const modelManager = new ModelManager();
+            modelManager.addCTOModel(`namespace org.acme@1.0.0
+            abstract concept Shape {
+                o Group group optional
+            }
+            concept Circle extends Shape {
+            }
+            concept Group {
+                o Circle first optional
+            }`, 'test.cto');
+            rustVisitor.isFieldRecursive(modelManager.getType('org.acme@1.0.0.Shape').getProperty('group')).should.equal(true);
+        });
 
+        it('should box the type of a recursive field', () => {
             let param = {
                 fileWriter: mockFileWriter
             };
+            mockField.isOptional.returns(true);
+            mockField.name = 'child';
+            mockField.type = 'Person';
+            sinon.stub(rustVisitor, 'getUnionDeclaration').returns(null);
+            sinon.stub(rustVisitor, 'isFieldRecursive').returns(true);
 
-            sinon.stub(rustVisitor, 'addUtilsModelFile');
-
+            rustVisitor.visitField(mockField, param);
 
-            let mockModelManager = sinon.createStubInstance(ModelManager);
-            mockModelManager.isModelManager.returns(true);
-            mockModelManager.getModelFiles.returns([{
-                accept: acceptSpy
-            },
-            {
-                accept: acceptSpy
-            }
-            ]);
+            param.fileWriter.writeLine.withArgs(1, 'pub child: Option<Box<Person>>,').calledOnce.should.be.ok;
+        });
+    });
 
-            mockModelManager.getNamespaces.returns([
-                'Goose'
-            ])
+    describe('toTraitPath', () => {
+        it('should qualify traits from other namespaces', () => {
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Asset');
+            mockClassDeclaration.getNamespace.returns('concerto@1.0.0');
+            rustVisitor.toTraitPath(mockClassDeclaration, 'concerto@1.0.0').should.equal('IAsset');
+            rustVisitor.toTraitPath(mockClassDeclaration, 'org.acme').should.equal('crate::lib::concerto_1_0_0::IAsset');
+        });
+    });
 
-            rustVisitor.visitModelManager(mockModelManager, param);
-            acceptSpy.withArgs(rustVisitor, param).calledTwice.should.be.ok;
-            param.fileWriter.openFile.withArgs('mod.rs').calledOnce.should.be.ok;
-            param.fileWriter.writeLine.withArgs(0, "pub mod goose;").calledOnce.should.be.ok;
-            param.fileWriter.writeLine.withArgs(0, "pub mod utils;").calledOnce.should.be.ok;
-            param.fileWriter.closeFile.calledOnce.should.be.ok;
+    describe('toUpperCamelCase', () => {
+        it('should convert namespaces to UpperCamelCase', () => {
+            rustVisitor.toUpperCamelCase('org.acme.hr@1.0.0').should.equal('OrgAcmeHr100');
         });
     });
 
-    describe('visitModelFile', () => {
+    describe('writeClassValidation', () => {
         let param;
         beforeEach(() => {
             param = {
                 fileWriter: mockFileWriter
             };
         });
-        it('should write lines for the imports that are not in own namespace (including super types)', () => {
-            let acceptSpy = sinon.spy();
-            let mockEnum = sinon.createStubInstance(EnumDeclaration);
-            mockEnum.isEnum.returns(true);
-            mockEnum.accept = acceptSpy;
 
-            let property1 = {
-                isPrimitive: () => {
-                    return false;
-                },
-                getFullyQualifiedTypeName: () => {
-                    return 'org.org1.Property1';
-                }
-            };
+        it('should write an empty validate method for a class without validators', () => {
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getProperties.returns([]);
+            mockClassDeclaration.getOwnProperties.returns([]);
 
-            let property2 = {
-                isPrimitive: () => {
-                    return false;
-                },
-                getFullyQualifiedTypeName: () => {
-                    return 'org.acme.Property2';
-                }
-            };
+            rustVisitor.writeClassValidation(mockClassDeclaration, param);
 
-            let property3 = {
-                isPrimitive: () => {
-                    return true;
-                },
-                getFullyQualifiedTypeName: () => {
-                    return 'super.Property3';
-                }
-            };
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'impl Bob {'],
+                [1, '/// Checks the Concerto validators declared on the fields of this type.'],
+                [1, 'pub fn validate(&self) -> std::result::Result<(), ValidationError> {'],
+                [2, 'Ok(())'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
 
+        it('should write validation and deserialization functions for validated fields', () => {
             let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
-            mockClassDeclaration.isEnum.returns(false);
-            mockClassDeclaration.getProperties.returns([property1, property2, property3]);
-            mockClassDeclaration.accept = acceptSpy;
+            const mockField = sinon.createStubInstance(Field);
+            mockField.isField.returns(true);
+            mockField.isPrimitive.returns(true);
+            mockField.isOptional.returns(true);
+            mockField.getName.returns('score');
+            mockField.getType.returns('Double');
+            mockField.getParent.returns(mockClassDeclaration);
+            mockField.getValidator.returns({
+                getLowerBound: () => 0,
+                getUpperBound: () => null,
+            });
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getProperties.returns([mockField]);
+            mockClassDeclaration.getOwnProperties.returns([mockField]);
 
-            let mockClassDeclaration2 = sinon.createStubInstance(ClassDeclaration);
-            mockClassDeclaration.isEnum.returns(false);
-            mockClassDeclaration2.getProperties.returns([]);
-            mockClassDeclaration2.accept = acceptSpy;
+            rustVisitor.writeClassValidation(mockClassDeclaration, param);
 
-            let mockModelFile = sinon.createStubInstance(ModelFile);
-            mockModelFile.getNamespace.returns('org.acme');
-            mockModelFile.getAllDeclarations.returns([
-                mockEnum,
-                mockClassDeclaration,
-                mockClassDeclaration2
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'impl Bob {'],
+                [1, '/// Checks the Concerto validators declared on the fields of this type.'],
+                [1, 'pub fn validate(&self) -> std::result::Result<(), ValidationError> {'],
+                [2, 'validate_bob_score(&self.score)?;'],
+                [2, 'Ok(())'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+                [0, 'pub fn validate_bob_score(value: &Option<f64>) -> std::result::Result<(), ValidationError> {'],
+                [1, 'if let Some(value) = value {'],
+                [2, 'validate_range("Bob.score", *value, Some(0.0), None)?;'],
+                [1, '}'],
+                [1, 'Ok(())'],
+                [0, '}'],
+                [0, ''],
+                [0, 'pub fn deserialize_bob_score<\'de, D>(deserializer: D) -> std::result::Result<Option<f64>, D::Error>'],
+                [0, 'where'],
+                [1, 'D: serde::Deserializer<\'de>,'],
+                [0, '{'],
+                [1, 'let value = <Option<f64>>::deserialize(deserializer)?;'],
+                [1, 'validate_bob_score(&value).map_err(serde::de::Error::custom)?;'],
+                [1, 'Ok(value)'],
+                [0, '}'],
+                [0, ''],
             ]);
-            mockModelFile.getImports.returns([
-                'org.org1.Import1',
-                'org.org1.Import2',
-                'org.org2.Import1',
-                'super.Property3',
-                'super.Parent'
+        });
+
+        it('should validate the structs and unions held by the fields', () => {
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            const field = (name, isArray, isOptional) => {
+                const mockField = sinon.createStubInstance(Field);
+                mockField.isField.returns(true);
+                mockField.isArray.returns(isArray);
+                mockField.isOptional.returns(isOptional);
+                mockField.getName.returns(name);
+                mockField.getType.returns('Address');
+                return mockField;
+            };
+            const mockState = field('state', false, false);
+            mockState.isTypeEnum.returns(true);
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getProperties.returns([
+                field('home', false, false), field('office', false, true), field('previous', true, false),
+                field('others', true, true), mockState,
             ]);
+            mockClassDeclaration.getOwnProperties.returns([]);
 
-            rustVisitor.visitModelFile(mockModelFile, param);
+            rustVisitor.writeClassValidation(mockClassDeclaration, param);
 
-            param.fileWriter.openFile.withArgs('org_acme.rs').calledOnce.should.be.ok;
-            param.fileWriter.writeLine.callCount.should.deep.equal(8);
             param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
-                [
-                    0, 'use serde::{ Deserialize, Serialize };'
-                ],
-                [
-                    0, 'use chrono::{ DateTime, TimeZone, Utc };'
-                ],
-                [
-                    1, ''
-                ],
-                [
-                    0, "use crate::lib::org_org1::*;"
-                ],
-                [
-                    0, "use crate::lib::org_org2::*;"
-                ],
-                [
-                    0, "use crate::lib::super_::*;"
-                ],
-                [
-                    0, "use crate::lib::utils::*;"
-                ],
-                [
-                    1, ""
-                ]
+                [0, 'impl Bob {'],
+                [1, '/// Checks the Concerto validators declared on the fields of this type.'],
+                [1, 'pub fn validate(&self) -> std::result::Result<(), ValidationError> {'],
+                [2, 'self.home.validate()?;'],
+                [2, 'if let Some(value) = &self.office {'],
+                [3, 'value.validate()?;'],
+                [2, '}'],
+                [2, 'for value in &self.previous {'],
+                [3, 'value.validate()?;'],
+                [2, '}'],
+                [2, 'for value in self.others.iter().flatten() {'],
+                [3, 'value.validate()?;'],
+                [2, '}'],
+                [2, 'Ok(())'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
             ]);
-            param.fileWriter.closeFile.calledOnce.should.be.ok;
-
-            acceptSpy.withArgs(rustVisitor, param).calledThrice.should.be.ok;
         });
-    });
 
+        it('should iterate over the elements of validated array fields', () => {
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            const mockField = sinon.createStubInstance(Field);
+            mockField.isField.returns(true);
+            mockField.isPrimitive.returns(true);
+            mockField.isArray.returns(true);
+            mockField.getName.returns('tags');
+            mockField.getType.returns('String');
+            mockField.getParent.returns(mockClassDeclaration);
+            mockField.getValidator.returns({
+                getRegex: () => /^[a-z]+$/,
+            });
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getProperties.returns([mockField]);
+            mockClassDeclaration.getOwnProperties.returns([mockField]);
 
-    describe('visitEnumDeclaration', () => {
-        it('should write the export enum and call accept on each property', () => {
-            let acceptSpy = sinon.spy();
-
-            let param = {
-                fileWriter: mockFileWriter
-            };
+            rustVisitor.writeClassValidation(mockClassDeclaration, param);
 
-            let mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
-            mockEnumDeclaration.isEnum.returns(true);
-            mockEnumDeclaration.getName.returns('Bob');
-            mockEnumDeclaration.getOwnProperties.returns([{
-                accept: acceptSpy
-            },
-            {
-                accept: acceptSpy
-            }]);
+            param.fileWriter.writeLine.withArgs(0, 'pub fn validate_bob_tags(value: &[String]) -> std::result::Result<(), ValidationError> {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(1, 'for value in value {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(2, 'static REGEX: std::sync::OnceLock<std::result::Result<regex::Regex, regex::Error>> = std::sync::OnceLock::new();').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(2, 'validate_regex("Bob.tags", value, &REGEX, r#"^[a-z]+$"#)?;').calledOnce.should.be.ok;
+        });
 
-            rustVisitor.visitEnumDeclaration(mockEnumDeclaration, param);
+        it('should check the validated scalars of a struct in its validate method', () => {
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            const mockField = (name, array, optional) => {
+                const field = sinon.createStubInstance(Field);
+                field.isField.returns(true);
+                field.isTypeScalar.returns(true);
+                field.isArray.returns(array);
+                field.isOptional.returns(optional);
+                field.getName.returns(name);
+                field.getType.returns('SSN');
+                field.getScalarField.returns({
+                    getType: () => 'String',
+                    getValidator: () => ({ getRegex: () => /\d{3}-\d{2}-\d{4}/ }),
+                });
+                return field;
+            };
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getProperties.returns([
+                mockField('ssn', false, false),
+                mockField('previousSsn', false, true),
+                mockField('ssns', true, false),
+                mockField('oldSsns', true, true),
+            ]);
+            mockClassDeclaration.getOwnProperties.returns([]);
 
-            param.fileWriter.writeLine.callCount.should.deep.equal(3);
-            param.fileWriter.writeLine.withArgs(0, 'pub enum Bob {').calledOnce.should.be.ok;
-            param.fileWriter.writeLine.withArgs(0, '}\n').calledOnce.should.be.ok;
+            rustVisitor.writeValidateMethod(mockClassDeclaration, param);
 
-            acceptSpy.withArgs(rustVisitor, param).calledTwice.should.be.ok;
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, 'impl Bob {'],
+                [1, '/// Checks the Concerto validators declared on the fields of this type.'],
+                [1, 'pub fn validate(&self) -> std::result::Result<(), ValidationError> {'],
+                [2, 'SSN::validate(self.ssn.value())?;'],
+                [2, 'if let Some(value) = &self.previous_ssn {'],
+                [3, 'SSN::validate(value.value())?;'],
+                [2, '}'],
+                [2, 'for value in &self.ssns {'],
+                [3, 'SSN::validate(value.value())?;'],
+                [2, '}'],
+                [2, 'for value in self.old_ssns.iter().flatten() {'],
+                [3, 'SSN::validate(value.value())?;'],
+                [2, '}'],
+                [2, 'Ok(())'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
         });
     });
 
-    describe('visitClassDeclaration', () => {
+    describe('visitScalarDeclaration', () => {
         let param;
         beforeEach(() => {
             param = {
                 fileWriter: mockFileWriter
             };
         });
-        it('should write the struct opening and close', () => {
-            let acceptSpy = sinon.spy();
+        it('should write a transparent newtype for a scalar', () => {
+            let mockScalarDeclaration = sinon.createStubInstance(ScalarDeclaration);
+            mockScalarDeclaration.isScalarDeclaration.returns(true);
+            mockScalarDeclaration.getName.returns('SSN');
+            mockScalarDeclaration.getType.returns('String');
 
-            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
-            mockClassDeclaration.isClassDeclaration.returns(true);
-            mockClassDeclaration.getProperties.returns([{
-                accept: acceptSpy
-            },
-            {
-                accept: acceptSpy
-            }]);
-            mockClassDeclaration.getName.returns('Bob');
+            rustVisitor.visitScalarDeclaration(mockScalarDeclaration, param);
 
-            rustVisitor.visitClassDeclaration(mockClassDeclaration, param);
-            param.fileWriter.writeLine.callCount.should.deep.equal(10);
             param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
-                [
-                    0, "#[derive(Debug, Serialize, Deserialize)]"
-                ],
-                [
-                    0, "pub struct Bob {"
-                ],
-                [
-                    1, "#[serde("
-                ],
-                [
-                    2, "rename = \"$class\","
-                ],
-                [
-                    1, ")]"
-                ],
-                [
-                    1, "pub _class: String,"
-                ],
-                [
-                    1, ""
-                ],
-                [
-                    1, ""
-                ],
-                [
-                    0, "}"
-                ],
-                [
-                    0, ""
-                ]
-            ])
+                [0, '#[derive(Debug, Serialize, Deserialize)]'],
+                [0, '#[serde(transparent)]'],
+                [0, 'pub struct SSN(pub String);'],
+                [0, ''],
+            ]);
         });
-    });
 
+        it('should implement Default for a scalar with a default value', () => {
+            let mockScalarDeclaration = sinon.createStubInstance(ScalarDeclaration);
+            mockScalarDeclaration.isScalarDeclaration.returns(true);
+            mockScalarDeclaration.getName.returns('SSN');
+            mockScalarDeclaration.getType.returns('String');
+            mockScalarDeclaration.getDefaultValue.returns('000-00-0000');
+
+            rustVisitor.visitScalarDeclaration(mockScalarDeclaration, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, '#[derive(Debug, Serialize, Deserialize)]'],
+                [0, '#[serde(transparent)]'],
+                [0, 'pub struct SSN(pub String);'],
+                [0, ''],
+                [0, 'impl Default for SSN {'],
+                [1, 'fn default() -> Self {'],
+                [2, 'Self(String::from(r#"000-00-0000"#))'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should write a validating constructor and deserializer for a scalar with validators', () => {
+            let mockScalarDeclaration = sinon.createStubInstance(ScalarDeclaration);
+            mockScalarDeclaration.isScalarDeclaration.returns(true);
+            mockScalarDeclaration.getName.returns('SSN');
+            mockScalarDeclaration.getType.returns('String');
+            mockScalarDeclaration.getValidator.returns({
+                getRegex: () => /\d{3}-\d{2}-\d{4}/,
+            });
+
+            rustVisitor.visitScalarDeclaration(mockScalarDeclaration, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, '#[derive(Debug, Serialize)]'],
+                [0, '#[serde(transparent)]'],
+                [0, 'pub struct SSN(String);'],
+                [0, ''],
+                [0, 'impl SSN {'],
+                [1, '/// Creates a new SSN, checking the Concerto validators of the scalar.'],
+                [1, 'pub fn new(value: String) -> std::result::Result<Self, ValidationError> {'],
+                [2, 'Self::validate(&value)?;'],
+                [2, 'Ok(Self(value))'],
+                [1, '}'],
+                [0, ''],
+                [1, '/// Returns the value of the SSN.'],
+                [1, 'pub fn value(&self) -> &str {'],
+                [2, '&self.0'],
+                [1, '}'],
+                [0, ''],
+                [1, '/// Consumes the SSN, returning its value.'],
+                [1, 'pub fn into_inner(self) -> String {'],
+                [2, 'self.0'],
+                [1, '}'],
+                [0, ''],
+                [1, '/// Checks a value against the Concerto validators of SSN.'],
+                [1, 'pub fn validate(value: &str) -> std::result::Result<(), ValidationError> {'],
+                [2, 'static REGEX: std::sync::OnceLock<std::result::Result<regex::Regex, regex::Error>> = std::sync::OnceLock::new();'],
+                [2, 'validate_regex("SSN", value, &REGEX, r#"\\d{3}-\\d{2}-\\d{4}"#)?;'],
+                [2, 'Ok(())'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+                [0, 'impl<\'de> Deserialize<\'de> for SSN {'],
+                [1, 'fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>'],
+                [1, 'where'],
+                [2, 'D: serde::Deserializer<\'de>,'],
+                [1, '{'],
+                [2, 'let value = String::deserialize(deserializer)?;'],
+                [2, 'Self::new(value).map_err(serde::de::Error::custom)'],
+                [1, '}'],
+                [0, '}'],
+                [0, ''],
+            ]);
+        });
+
+        it('should check the length bounds of a String scalar', () => {
+            let mockScalarDeclaration = sinon.createStubInstance(ScalarDeclaration);
+            mockScalarDeclaration.isScalarDeclaration.returns(true);
+            mockScalarDeclaration.getName.returns('Code');
+            mockScalarDeclaration.getType.returns('String');
+            mockScalarDeclaration.getValidator.returns({
+                getRegex: () => null,
+                getMinLength: () => 1,
+                getMaxLength: () => null,
+            });
+
+            rustVisitor.visitScalarDeclaration(mockScalarDeclaration, param);
+
+            param.fileWriter.writeLine.withArgs(2, 'validate_length("Code", value, Some(1), None)?;').calledOnce.should.be.ok;
+        });
+
+        it('should write a newtype with datetime serializers for a DateTime scalar', () => {
+            let mockScalarDeclaration = sinon.createStubInstance(ScalarDeclaration);
+            mockScalarDeclaration.isScalarDeclaration.returns(true);
+            mockScalarDeclaration.getName.returns('Timestamp');
+            mockScalarDeclaration.getType.returns('DateTime');
+
+            rustVisitor.visitScalarDeclaration(mockScalarDeclaration, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [0, '#[derive(Debug, Serialize, Deserialize)]'],
+                [0, '#[serde(transparent)]'],
+                [0, 'pub struct Timestamp('],
+                [1, '#[serde('],
+                [2, 'serialize_with = "serialize_datetime",'],
+                [2, 'deserialize_with = "deserialize_datetime",'],
+                [1, ')]'],
+                [1, 'pub DateTime<Utc>,'],
+                [0, ');'],
+                [0, ''],
+            ]);
+        });
+    });
 
     describe('visitField', () => {
         let param;
@@ -375,7 +2355,7 @@ describe('RustVisitor', function () {
                 [
                     1, "pub name: String,"
                 ]
-            ])
+            ]);
         });
 
         it('should write a line for field name and type thats an array', () => {
@@ -410,7 +2390,7 @@ describe('RustVisitor', function () {
                     1, "pub bob: Vec<Person>,"
                 ]
 
-            ])
+            ]);
 
         });
 
@@ -428,6 +2408,7 @@ describe('RustVisitor', function () {
             mockModelManager.getType.returns(mockClassDeclaration);
             mockModelFile.getModelManager.returns(mockModelManager);
             mockClassDeclaration.getModelFile.returns(mockModelFile);
+            mockClassDeclaration.getAssignableClassDeclarations.returns([mockClassDeclaration]);
             mockField.getParent.returns(mockClassDeclaration);
             rustVisitor.visitField(mockField, param);
 
@@ -448,10 +2429,54 @@ describe('RustVisitor', function () {
                     1, "pub bob: Option<String>,"
                 ]
 
-            ])
+            ]);
+
+        });
+
+
+        it('should write a line using the scalar newtype for a scalar field', () => {
+            const mockField = sinon.createStubInstance(Field);
+            mockField.isPrimitive.returns(false);
+            mockField.isTypeScalar.returns(true);
+            mockField.name = 'ssn';
+            mockField.type = 'SSN';
+            rustVisitor.visitField(mockField, param);
 
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [1, '#[serde('],
+                [2, 'rename = "ssn",'],
+                [1, ')]'],
+                [1, 'pub ssn: SSN,'],
+            ]);
         });
 
+        it('should write a line with a validating deserializer for a field with a validator', () => {
+            const mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Person');
+            const mockField = sinon.createStubInstance(Field);
+            mockField.isField.returns(true);
+            mockField.isPrimitive.returns(true);
+            mockField.isOptional.returns(true);
+            mockField.name = 'firstName';
+            mockField.type = 'String';
+            mockField.getName.returns('firstName');
+            mockField.getType.returns('String');
+            mockField.getParent.returns(mockClassDeclaration);
+            mockField.getValidator.returns({
+                getRegex: () => /^[A-Z]/,
+            });
+            rustVisitor.visitField(mockField, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [1, '#[serde('],
+                [2, 'rename = "firstName",'],
+                [2, 'skip_serializing_if = "Option::is_none",'],
+                [2, 'default,'],
+                [2, 'deserialize_with = "deserialize_person_first_name",'],
+                [1, ')]'],
+                [1, 'pub first_name: Option<String>,'],
+            ]);
+        });
 
         it('should write a line with serializer for date field', () => {
             const mockField = sinon.createStubInstance(Field);
@@ -466,31 +2491,136 @@ describe('RustVisitor', function () {
             mockModelManager.getType.returns(mockClassDeclaration);
             mockModelFile.getModelManager.returns(mockModelManager);
             mockClassDeclaration.getModelFile.returns(mockModelFile);
+            mockClassDeclaration.getAssignableClassDeclarations.returns([mockClassDeclaration]);
             mockField.getParent.returns(mockClassDeclaration);
             rustVisitor.visitField(mockField, param);
 
             param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
-                [
-                    1, "#[serde("
-                ],
-                [
-                    2, "rename = \"timestamp\","
-                ],
-                [
-                    2, "serialize_with = \"serialize_datetime\","
-                ],
-                [
-                    2, "deserialize_with = \"deserialize_datetime\","
-                ],
-                [
-                    1, ")]"
-                ],
-                [
-                    1, "pub timestamp: DateTime<Utc>,"
-                ]
+                [
+                    1, '#[serde('
+                ],
+                [
+                    2, 'rename = "timestamp",'
+                ],
+                [
+                    2, 'serialize_with = "serialize_datetime",'
+                ],
+                [
+                    2, 'deserialize_with = "deserialize_datetime",'
+                ],
+                [
+                    1, ')]'
+                ],
+                [
+                    1, 'pub timestamp: DateTime<Utc>,'
+                ]
+
+            ]);
+
+        });
+
+        it('should default a missing optional date field to None', () => {
+            const mockField = sinon.createStubInstance(Field);
+            mockField.isPrimitive.returns(true);
+            mockField.isOptional.returns(true);
+            mockField.name = 'expiry';
+            mockField.type = 'DateTime';
+
+            rustVisitor.visitField(mockField, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [1, '#[serde('],
+                [2, 'rename = "expiry",'],
+                [2, 'skip_serializing_if = "Option::is_none",'],
+                [2, 'default,'],
+                [2, 'serialize_with = "serialize_datetime_option",'],
+                [2, 'deserialize_with = "deserialize_datetime_option",'],
+                [1, ')]'],
+                [1, 'pub expiry: Option<DateTime<Utc>>,'],
+            ]);
+        });
+
+        it('should write a line with serializer for date array field', () => {
+            const mockField = sinon.createStubInstance(Field);
+            mockField.isPrimitive.returns(true);
+            mockField.isArray.returns(true);
+            mockField.name = 'holidays';
+            mockField.type = 'DateTime';
+
+            rustVisitor.visitField(mockField, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [1, '#[serde('],
+                [2, 'rename = "holidays",'],
+                [2, 'serialize_with = "serialize_datetime_vec",'],
+                [2, 'deserialize_with = "deserialize_datetime_vec",'],
+                [1, ')]'],
+                [1, 'pub holidays: Vec<DateTime<Utc>>,'],
+            ]);
+        });
+
+        it('should write a line with serializer for optional date array field', () => {
+            const mockField = sinon.createStubInstance(Field);
+            mockField.isPrimitive.returns(true);
+            mockField.isArray.returns(true);
+            mockField.isOptional.returns(true);
+            mockField.name = 'closures';
+            mockField.type = 'DateTime';
+
+            rustVisitor.visitField(mockField, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [1, '#[serde('],
+                [2, 'rename = "closures",'],
+                [2, 'skip_serializing_if = "Option::is_none",'],
+                [2, 'default,'],
+                [2, 'serialize_with = "serialize_datetime_vec_option",'],
+                [2, 'deserialize_with = "deserialize_datetime_vec_option",'],
+                [1, ')]'],
+                [1, 'pub closures: Option<Vec<DateTime<Utc>>>,'],
+            ]);
+        });
+
+        it('should default the $timestamp system property to the current time', () => {
+            const mockField = sinon.createStubInstance(Field);
+            mockField.isPrimitive.returns(true);
+            mockField.name = '$timestamp';
+            mockField.type = 'DateTime';
+
+            rustVisitor.visitField(mockField, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [1, '#[serde('],
+                [2, 'rename = "$timestamp",'],
+                [2, 'serialize_with = "serialize_datetime",'],
+                [2, 'deserialize_with = "deserialize_datetime",'],
+                [2, 'default = "default_timestamp",'],
+                [1, ')]'],
+                [1, 'pub _timestamp: DateTime<Utc>,'],
+            ]);
+        });
+        it('should default a missing field to its modelled default', () => {
+            const mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Address');
+            const mockField = sinon.createStubInstance(Field);
+            mockField.isField.returns(true);
+            mockField.isPrimitive.returns(true);
+            mockField.getName.returns('city');
+            mockField.getType.returns('String');
+            mockField.getDefaultValue.returns('Winchester');
+            mockField.getParent.returns(mockClassDeclaration);
+            mockField.name = 'city';
+            mockField.type = 'String';
 
-            ])
+            rustVisitor.visitField(mockField, param);
 
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [1, '#[serde('],
+                [2, 'rename = "city",'],
+                [2, 'default = "default_address_city",'],
+                [1, ')]'],
+                [1, 'pub city: String,'],
+            ]);
         });
     });
 
@@ -503,16 +2633,39 @@ describe('RustVisitor', function () {
             let mockEnumValueDeclaration = sinon.createStubInstance(EnumValueDeclaration);
             mockEnumValueDeclaration.isEnumValue.returns(true);
             mockEnumValueDeclaration.getName.returns('Bob');
+            let mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
+            mockEnumDeclaration.getOwnProperties.returns([mockEnumValueDeclaration]);
+            mockEnumValueDeclaration.getParent.returns(mockEnumDeclaration);
 
             rustVisitor.visitEnumValueDeclaration(mockEnumValueDeclaration, param);
 
             param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
                 [
                     1,
-                    "Bob,"
+                    'Bob,'
                 ]
-            ])
+            ]);
+
+        });
+
+        it('should rename a variant to the name of the enum value', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
 
+            let mockEnumValueDeclaration = sinon.createStubInstance(EnumValueDeclaration);
+            mockEnumValueDeclaration.isEnumValue.returns(true);
+            mockEnumValueDeclaration.getName.returns('PASSED_TESTING');
+            let mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
+            mockEnumDeclaration.getOwnProperties.returns([mockEnumValueDeclaration]);
+            mockEnumValueDeclaration.getParent.returns(mockEnumDeclaration);
+
+            rustVisitor.visitEnumValueDeclaration(mockEnumValueDeclaration, param);
+
+            param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
+                [1, '#[serde(rename = "PASSED_TESTING")]'],
+                [1, 'PassedTesting,'],
+            ]);
         });
     });
 
@@ -522,6 +2675,7 @@ describe('RustVisitor', function () {
             param = {
                 fileWriter: mockFileWriter
             };
+            sinon.stub(rustVisitor, 'getUnionDeclaration').returns(null);
         });
         it('should write a line for field name and type', () => {
             let mockRelationship = sinon.createStubInstance(RelationshipDeclaration);
@@ -532,12 +2686,18 @@ describe('RustVisitor', function () {
 
             param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
                 [
-                    1, "#[serde(rename = \"Bob\")]"
+                    1, '#[serde('
                 ],
                 [
-                    1, "pub bob: Person,"
+                    2, 'rename = "Bob",'
+                ],
+                [
+                    1, ')]'
+                ],
+                [
+                    1, 'pub bob: Relationship<Person>,'
                 ]
-            ])
+            ]);
         });
 
         it('should write a line for field name and type thats an array', () => {
@@ -551,12 +2711,18 @@ describe('RustVisitor', function () {
 
             param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
                 [
-                    1, "#[serde(rename = \"Bob\")]"
+                    1, '#[serde('
                 ],
                 [
-                    1, "pub bob: Vec<Person>,"
+                    2, 'rename = "Bob",'
+                ],
+                [
+                    1, ')]'
+                ],
+                [
+                    1, 'pub bob: Vec<Relationship<Person>>,'
                 ]
-            ])
+            ]);
         });
 
         it('should write a line for optional field name', () => {
@@ -570,15 +2736,123 @@ describe('RustVisitor', function () {
 
             param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
                 [
-                    1, "#[serde(rename = \"Bob\")]"
+                    1, '#[serde('
+                ],
+                [
+                    2, 'rename = "Bob",'
+                ],
+                [
+                    2, 'skip_serializing_if = "Option::is_none",'
+                ],
+                [
+                    1, ')]'
                 ],
                 [
-                    1, "pub bob: Option<Person>,"
+                    1, 'pub bob: Option<Relationship<Person>>,'
                 ]
-            ])
+            ]);
+        });
+
+        it('should use the union of a super type as the target type', () => {
+            let mockRelationship = sinon.createStubInstance(RelationshipDeclaration);
+            const mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getName.returns('Person');
+            rustVisitor.getUnionDeclaration.returns(mockClassDeclaration);
+
+            mockRelationship.isRelationship.returns(true);
+            mockRelationship.name = 'Bob';
+            mockRelationship.type = 'Person';
+            rustVisitor.visitRelationshipDeclaration(mockRelationship, param);
+
+            param.fileWriter.writeLine.withArgs(1, 'pub bob: Relationship<PersonUnion>,').calledOnce.should.be.ok;
+        });
+
+        it('should return the relationship type of a relationship', () => {
+            let mockRelationship = sinon.createStubInstance(RelationshipDeclaration);
+            mockRelationship.isRelationship.returns(true);
+            mockRelationship.getType.returns('Person');
+            mockRelationship.isOptional.returns(true);
+            rustVisitor.toFieldRustType(mockRelationship).should.equal('Option<Relationship<Person>>');
+        });
+    });
+
+    describe('addEnumUtils', () => {
+        it('should add the enum error', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            rustVisitor.addEnumUtils(param);
+            param.fileWriter.writeLine.withArgs(0, 'pub struct EnumError {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(1, 'pub fn new(name: &str, value: &str) -> Self {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, 'impl std::error::Error for EnumError {}').calledOnce.should.be.ok;
+        });
+    });
+
+    describe('addRelationshipUtils', () => {
+        it('should add the generic relationship type', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            rustVisitor.addRelationshipUtils(param);
+            param.fileWriter.writeLine.withArgs(0, 'pub struct Relationship<T> {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(1, 'target: std::marker::PhantomData<fn() -> T>,').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(1, 'pub fn parse(uri: &str) -> std::result::Result<Self, RelationshipError> {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, 'impl<T> Serialize for Relationship<T> {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, 'impl<\'de, T: ConcertoClass> Deserialize<\'de> for Relationship<T> {').calledOnce.should.be.ok;
+        });
+
+        it('should only parse the resource URIs of the classes of the target type', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            rustVisitor.addRelationshipUtils(param);
+            param.fileWriter.writeLine.withArgs(0, 'impl<T: ConcertoClass> Relationship<T> {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(2, 'if !T::CLASSES.contains(&fqn) {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(3, 'return Err(error(&format!("expected a resource of type {}", T::CLASSES.join(" or "))));').calledOnce.should.be.ok;
+        });
+    });
+
+
+    describe('addClassUtils', () => {
+        it('should add the Class type of the $class field', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            rustVisitor.addClassUtils(param);
+            param.fileWriter.writeLine.withArgs(0, 'pub trait ConcertoClass {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(1, 'const CLASSES: &\'static [&\'static str] = &[Self::CLASS];').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, 'pub struct Class<T>(std::marker::PhantomData<T>);').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(2, 'serializer.serialize_str(T::CLASS)').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(4, '"invalid $class: expected {}, found {}",').calledOnce.should.be.ok;
+        });
+    });
+
+    describe('getStructProperties', () => {
+        it('should not store the $identifier of an explicitly identified class', () => {
+            const mockIdentifier = sinon.createStubInstance(Field);
+            mockIdentifier.getName.returns('$identifier');
+            const mockEmail = sinon.createStubInstance(Field);
+            mockEmail.getName.returns('email');
+            let mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.getProperties.returns([mockIdentifier, mockEmail]);
+
+            rustVisitor.getStructProperties(mockClassDeclaration).should.deep.equal([mockIdentifier, mockEmail]);
+            mockClassDeclaration.isExplicitlyIdentified.returns(true);
+            rustVisitor.getStructProperties(mockClassDeclaration).should.deep.equal([mockEmail]);
         });
     });
 
+    describe('addIdentifiableUtils', () => {
+        it('should add the Identifiable trait', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            rustVisitor.addIdentifiableUtils(param);
+            param.fileWriter.writeLine.withArgs(0, 'pub trait Identifiable {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(1, 'fn identifier(&self) -> &str;').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(1, 'fn to_relationship(&self) -> Relationship<Self>').calledOnce.should.be.ok;
+        });
+    });
 
     describe('toRustType', () => {
         it('should return Date for DateTime', () => {
@@ -593,8 +2867,141 @@ describe('RustVisitor', function () {
         it('should return number for Double', () => {
             rustVisitor.toRustType('Double').should.deep.equal('f64');
         });
-        it('should return number for Long', () => {
-            rustVisitor.toRustType('Long').should.deep.equal('u64');
+        it('should return a signed 64 bit integer for Long', () => {
+            rustVisitor.toRustType('Long').should.deep.equal('i64');
+        });
+        it('should return a signed 32 bit integer for Integer', () => {
+            rustVisitor.toRustType('Integer').should.deep.equal('i32');
+        });
+        it('should return the type name for other types', () => {
+            rustVisitor.toRustType('Person').should.deep.equal('Person');
+        });
+        it('should use the primitive types from the parameters', () => {
+            const param = { primitiveTypes: { Long: 'i128', Double: 'f32' } };
+            rustVisitor.toRustType('Long', param).should.deep.equal('i128');
+            rustVisitor.toRustType('Double', param).should.deep.equal('f32');
+            rustVisitor.toRustType('Integer', param).should.deep.equal('i32');
+        });
+        it('should return the DateTime type of the time library', () => {
+            rustVisitor.toRustType('DateTime', { timeLibrary: 'chrono' }).should.deep.equal('DateTime<Utc>');
+            rustVisitor.toRustType('DateTime', { timeLibrary: 'chrono-fixed-offset' }).should.deep.equal('DateTime<FixedOffset>');
+            rustVisitor.toRustType('DateTime', { timeLibrary: 'time' }).should.deep.equal('OffsetDateTime');
+            rustVisitor.toRustType('DateTime', { timeLibrary: 'time', primitiveTypes: { DateTime: 'Timestamp' } }).should.deep.equal('Timestamp');
+        });
+    });
+
+    describe('getTimeLibrary', () => {
+        it('should default to chrono', () => {
+            rustVisitor.getTimeLibrary({}).imports.should.equal('use chrono::{ DateTime, Utc };');
+        });
+        it('should throw for an unsupported time library', () => {
+            (() => rustVisitor.getTimeLibrary({ timeLibrary: 'jiff' }))
+                .should.throw('Unsupported time library: jiff. Use one of chrono, chrono-fixed-offset, time.');
+        });
+    });
+
+    describe('toRustRegex', () => {
+        it('should return the source of a regex without flags', () => {
+            rustVisitor.toRustRegex(/^[a-z]+$/).should.equal('^[a-z]+$');
+        });
+        it('should convert supported flags to inline flags', () => {
+            rustVisitor.toRustRegex(/abc/gimu).should.equal('(?im)abc');
+        });
+        it('should throw for look-around assertions', () => {
+            (() => rustVisitor.toRustRegex(/^(?!ZZZ)[A-Z]{3}$/, 'Currency')).should.throw('Unsupported regex /^(?!ZZZ)[A-Z]{3}$/ for Currency: the Rust regex crate does not support look-around assertions.');
+            (() => rustVisitor.toRustRegex(/(?<=\$)\d+/, 'Price')).should.throw(/look-around assertions/);
+        });
+        it('should throw for backreferences', () => {
+            (() => rustVisitor.toRustRegex(/(a)\1/, 'Twice')).should.throw(/does not support backreferences/);
+            (() => rustVisitor.toRustRegex(/(?<a>x)\k<a>/, 'Twice')).should.throw(/does not support backreferences/);
+        });
+        it('should accept escaped parentheses and character classes that look like look-arounds', () => {
+            rustVisitor.toRustRegex(/\(?=[(?!]\\1/, 'Code').should.equal('\\(?=[(?!]\\\\1');
+        });
+    });
+
+    describe('toRustRawString', () => {
+        it('should write a raw string literal', () => {
+            rustVisitor.toRustRawString('a"b').should.equal('r#"a"b"#');
+        });
+        it('should add hashes when the string contains the terminator', () => {
+            rustVisitor.toRustRawString('a"#b').should.equal('r##"a"#b"##');
+        });
+    });
+
+    describe('toRustNumber', () => {
+        it('should add a decimal point to whole numbers for f64', () => {
+            rustVisitor.toRustNumber(10, 'f64').should.equal('10.0');
+            rustVisitor.toRustNumber(-1.5, 'f64').should.equal('-1.5');
+        });
+        it('should keep integers as is for integer types', () => {
+            rustVisitor.toRustNumber(10, 'i64').should.equal('10');
+            rustVisitor.toRustNumber(10, 'f32').should.equal('10.0');
+        });
+    });
+
+    describe('addValidationUtils', () => {
+        it('should add the validation error and functions', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            rustVisitor.addValidationUtils(param);
+            param.fileWriter.writeLine.withArgs(0, 'pub struct ValidationError {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, 'impl std::error::Error for ValidationError {}').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, 'pub fn validate_regex(').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(1, 'regex: &std::sync::OnceLock<std::result::Result<regex::Regex, regex::Error>>,').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(2, '.get_or_init(|| regex::Regex::new(pattern))').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, 'pub fn validate_length(path: &str, value: &str, min: Option<usize>, max: Option<usize>) -> std::result::Result<(), ValidationError> {').calledOnce.should.be.ok;
+            param.fileWriter.writeLine.withArgs(0, 'pub fn validate_range<T>(path: &str, value: T, lower: Option<T>, upper: Option<T>) -> std::result::Result<(), ValidationError>').calledOnce.should.be.ok;
+        });
+    });
+
+    describe('addDateTimeUtils', () => {
+        it('should add the serde helpers of required, optional and array DateTime fields', () => {
+            let param = {
+                fileWriter: mockFileWriter
+            };
+            rustVisitor.addDateTimeUtils(param);
+            const lines = param.fileWriter.writeLine.getCalls().map(call => call.args[1]);
+            lines.should.include('pub fn serialize_datetime<S>(datetime: &DateTime<Utc>, serializer: S) -> std::result::Result<S::Ok, S::Error>');
+            lines.should.include('None => serializer.serialize_none(),');
+            lines.should.include('match Option::<String>::deserialize(deserializer)? {');
+            lines.should.include('pub fn serialize_datetime_vec<S>(datetimes: &[DateTime<Utc>], serializer: S) -> std::result::Result<S::Ok, S::Error>');
+            lines.should.include('pub fn deserialize_datetime_vec<\'de, D>(deserializer: D) -> std::result::Result<Vec<DateTime<Utc>>, D::Error>');
+            lines.should.include('pub fn serialize_datetime_vec_option<S>(datetimes: &Option<Vec<DateTime<Utc>>>, serializer: S) -> std::result::Result<S::Ok, S::Error>');
+            lines.should.include('match Option::<Vec<String>>::deserialize(deserializer)? {');
+            lines.should.include('pub fn default_timestamp() -> DateTime<Utc> {');
+            lines.should.not.include('_ => unreachable!(),');
+            lines.should.include('match DateTime::parse_from_rfc3339(value) {');
+            lines.should.include('datetime.to_rfc3339_opts(SecondsFormat::Millis, true)');
+        });
+
+        it('should keep the offset of DateTime values with chrono-fixed-offset', () => {
+            let param = {
+                fileWriter: mockFileWriter,
+                timeLibrary: 'chrono-fixed-offset'
+            };
+            rustVisitor.addDateTimeUtils(param);
+            const lines = param.fileWriter.writeLine.getCalls().map(call => call.args[1]);
+            lines.should.include('pub fn parse_datetime(value: &str) -> std::result::Result<DateTime<FixedOffset>, chrono::ParseError> {');
+            lines.should.include('.map(|datetime| datetime.and_utc().fixed_offset())');
+            lines.should.include('pub fn deserialize_datetime_option<\'de, D>(deserializer: D) -> std::result::Result<Option<DateTime<FixedOffset>>, D::Error>');
+            lines.should.include('Utc::now().fixed_offset()');
+        });
+
+        it('should add the helpers of OffsetDateTime with time', () => {
+            let param = {
+                fileWriter: mockFileWriter,
+                timeLibrary: 'time'
+            };
+            rustVisitor.addDateTimeUtils(param);
+            const lines = param.fileWriter.writeLine.getCalls().map(call => call.args[1]);
+            lines.should.include('pub fn parse_datetime(value: &str) -> std::result::Result<OffsetDateTime, time::error::Parse> {');
+            lines.should.include('pub fn format_datetime(datetime: &OffsetDateTime) -> String {');
+            lines.should.include('pub fn serialize_datetime_vec<S>(datetimes: &[OffsetDateTime], serializer: S) -> std::result::Result<S::Ok, S::Error>');
+            lines.should.include('pub fn default_timestamp() -> OffsetDateTime {');
+            lines.should.include('OffsetDateTime::now_utc()');
+            lines.filter(line => /chrono|Utc\b/.test(line)).should.deep.equal([]);
         });
     });
 
@@ -607,169 +3014,33 @@ describe('RustVisitor', function () {
             };
         });
         it('should add utils file', () => {
+            let mockAddDateTimeUtils = sinon.stub(rustVisitor, 'addDateTimeUtils');
+            let mockAddValidationUtils = sinon.stub(rustVisitor, 'addValidationUtils');
+            let mockAddEnumUtils = sinon.stub(rustVisitor, 'addEnumUtils');
+            let mockAddRelationshipUtils = sinon.stub(rustVisitor, 'addRelationshipUtils');
+            let mockAddIdentifiableUtils = sinon.stub(rustVisitor, 'addIdentifiableUtils');
+            let mockAddClassUtils = sinon.stub(rustVisitor, 'addClassUtils');
             rustVisitor.addUtilsModelFile(param);
+            mockAddDateTimeUtils.calledWith(param).should.be.ok;
+            mockAddClassUtils.calledWith(param).should.be.ok;
+            mockAddValidationUtils.calledWith(param).should.be.ok;
+            mockAddEnumUtils.calledWith(param).should.be.ok;
+            mockAddRelationshipUtils.calledWith(param).should.be.ok;
+            mockAddIdentifiableUtils.calledWith(param).should.be.ok;
             param.fileWriter.writeLine.getCalls().map(call => call.args).should.deep.equal([
                 [
                     0,
-                    "use chrono::{ DateTime, TimeZone, Utc };"
-                ],
-                [
-                    0,
-                    "use serde::{ Deserialize, Serialize, Deserializer, Serializer };"
-                ],
-                [
-                    1,
-                    ""
-                ],
-                [
-                    0,
-                    "pub fn serialize_datetime_option<S>(datetime: &Option<chrono::DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>"
-                ],
-                [
-                    0,
-                    "where"
-                ],
-                [
-                    1,
-                    "S: Serializer,"
-                ],
-                [
-                    0,
-                    "{"
-                ],
-                [
-                    1,
-                    "match datetime {"
-                ],
-                [
-                    2,
-                    "Some(dt) => {"
-                ],
-                [
-                    3,
-                    "serialize_datetime(&dt, serializer)"
-                ],
-                [
-                    2,
-                    "},"
-                ],
-                [
-                    2,
-                    "_ => unreachable!(),"
-                ],
-                [
-                    1,
-                    "}"
-                ],
-                [
-                    0,
-                    "}"
-                ],
-                [
-                    0,
-                    ""
-                ],
-                [
-                    0,
-                    "pub fn deserialize_datetime_option<'de, D>(deserializer: D) -> Result<Option<chrono::DateTime<Utc>>, D::Error>"
-                ],
-                [
-                    0,
-                    "where"
-                ],
-                [
-                    1,
-                    "D: Deserializer<'de>,"
-                ],
-                [
-                    0,
-                    "{"
-                ],
-                [
-                    1,
-                    "match deserialize_datetime(deserializer) {"
-                ],
-                [
-                    2,
-                    "Ok(result)=>Ok(Some(result)),"
-                ],
-                [
-                    2,
-                    "Err(error) => Err(error),"
-                ],
-                [
-                    1,
-                    "}"
-                ],
-                [
-                    0,
-                    "}"
-                ],
-                [
-                    0,
-                    ""
-                ],
-                [
-                    0,
-                    "pub fn deserialize_datetime<'de, D>(deserializer: D) -> Result<chrono::DateTime<Utc>, D::Error>"
-                ],
-                [
-                    0,
-                    "where"
-                ],
-                [
-                    1,
-                    "D: Deserializer<'de>,"
-                ],
-                [
-                    0,
-                    "{"
-                ],
-                [
-                    1,
-                    "let datetime_str = String::deserialize(deserializer)?;"
-                ],
-                [
-                    1,
-                    "Utc.datetime_from_str(&datetime_str, \"%Y-%m-%dT%H:%M:%S%.3f%Z\").map_err(serde::de::Error::custom)"
-                ],
-                [
-                    0,
-                    "}"
-                ],
-                [
-                    1,
-                    ""
-                ],
-                [
-                    0,
-                    "pub fn serialize_datetime<S>(datetime: &chrono::DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>"
-                ],
-                [
-                    0,
-                    "where"
-                ],
-                [
-                    1,
-                    "S: Serializer,"
+                    'use chrono::{ DateTime, NaiveDateTime, SecondsFormat, Utc };'
                 ],
                 [
                     0,
-                    "{"
-                ],
-                [
-                    1,
-                    "let datetime_str = datetime.format(\"%+\").to_string();"
+                    'use serde::{ Deserialize, Serialize, Deserializer, Serializer };'
                 ],
                 [
                     1,
-                    "serializer.serialize_str(&datetime_str)"
-                ],
-                [
-                    0,
-                    "}"
+                    ''
                 ]
-            ])
+            ]);
         })
     })
 
@@ -0,0 +1,107 @@
+/*
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+'use strict';
+
+const chai = require('chai');
+chai.should();
+const sinon = require('sinon');
+
+const RustVisitor = require('../../../../lib/codegen/fromcto/rust/rustvisitor.js');
+const AbstractRustPlugin = require('../../../../lib/codegen/abstractrustplugin.js');
+
+const ClassDeclaration = require('@accordproject/concerto-core').ClassDeclaration;
+const EnumDeclaration = require('@accordproject/concerto-core').EnumDeclaration;
+const ModelFile = require('@accordproject/concerto-core').ModelFile;
+const FileWriter = require('@accordproject/concerto-util').FileWriter;
+
+describe('RustMissingPlugin', function () {
+    let rustVisitor;
+    let mockFileWriter;
+    beforeEach(() => {
+        rustVisitor = new RustVisitor();
+        rustVisitor.plugin = new AbstractRustPlugin();
+        mockFileWriter = sinon.createStubInstance(FileWriter);
+    });
+
+    describe('visitModelFile', () => {
+        let param;
+        let mockModelFile;
+        beforeEach(() => {
+            param = {
+                fileWriter: mockFileWriter
+            };
+            mockModelFile = sinon.createStubInstance(ModelFile);
+            mockModelFile.getNamespace.returns('org.acme@1.0.0');
+            mockModelFile.getAllDeclarations.returns([]);
+            mockModelFile.getImports.returns([]);
+        });
+
+        it('should fail to write the use declarations of a model file', () => {
+            (function () { rustVisitor.visitModelFile(mockModelFile, param); }).should.throw('addFileImports not implemented in default plugin');
+        });
+
+        it('should fail to write the additional modules of a model file', () => {
+            (function () { rustVisitor.plugin.addFileModules(mockModelFile, param); }).should.throw('addFileModules not implemented in default plugin');
+        });
+    });
+
+    describe('visitEnumDeclaration', () => {
+        let param;
+        let mockEnumDeclaration;
+        beforeEach(() => {
+            param = {
+                fileWriter: mockFileWriter
+            };
+            mockEnumDeclaration = sinon.createStubInstance(EnumDeclaration);
+            mockEnumDeclaration.isEnum.returns(true);
+            mockEnumDeclaration.getName.returns('Bob');
+            mockEnumDeclaration.getOwnProperties.returns([]);
+        });
+
+        it('should fail to write an enum declaration', () => {
+            (function () { rustVisitor.visitEnumDeclaration(mockEnumDeclaration, param); }).should.throw('addEnumAttributes not implemented in default plugin');
+        });
+
+        it('should fail to write the additional use declarations, impl blocks and modules of an enum', () => {
+            (function () { rustVisitor.plugin.addEnumImports(mockEnumDeclaration, param); }).should.throw('addEnumImports not implemented in default plugin');
+            (function () { rustVisitor.plugin.addEnumImpls(mockEnumDeclaration, param); }).should.throw('addEnumImpls not implemented in default plugin');
+            (function () { rustVisitor.plugin.addEnumModules(mockEnumDeclaration, param); }).should.throw('addEnumModules not implemented in default plugin');
+        });
+    });
+
+    describe('visitClassDeclaration', () => {
+        let param;
+        let mockClassDeclaration;
+        beforeEach(() => {
+            param = {
+                fileWriter: mockFileWriter
+            };
+            mockClassDeclaration = sinon.createStubInstance(ClassDeclaration);
+            mockClassDeclaration.isClassDeclaration.returns(true);
+            mockClassDeclaration.getName.returns('Bob');
+            mockClassDeclaration.getProperties.returns([]);
+        });
+
+        it('should fail to write a class declaration', () => {
+            (function () { rustVisitor.visitClassDeclaration(mockClassDeclaration, param); }).should.throw('addClassAttributes not implemented in default plugin');
+        });
+
+        it('should fail to write the additional use declarations, impl blocks and modules of a class', () => {
+            (function () { rustVisitor.plugin.addClassImports(mockClassDeclaration, param); }).should.throw('addClassImports not implemented in default plugin');
+            (function () { rustVisitor.plugin.addClassImpls(mockClassDeclaration, param); }).should.throw('addClassImpls not implemented in default plugin');
+            (function () { rustVisitor.plugin.addClassModules(mockClassDeclaration, param); }).should.throw('addClassModules not implemented in default plugin');
+        });
+    });
+});
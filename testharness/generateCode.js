@@ -2,24 +2,32 @@ const fs = require('fs');
 const fse = require('fs-extra'); // Add this line to require fs-extra
 const { ModelManager } = require('@accordproject/concerto-core');
 const { FileWriter } = require('@accordproject/concerto-util');
-const Visitor = require('../lib/codegen/fromcto/rust/rustvisitor');
+const Visitor = require('../lib/codegen/codegen').formats.rust;
+
+const USAGE = 'Usage: node generateCode.js <model-filename> <output-directory> [<model-cache-directory>] [--offline]';
 
 async function main() {
   try {
-    const modelFilePath = process.argv[2];
+    const offline = process.argv.includes('--offline');
+    const args = process.argv.slice(2).filter(arg => arg !== '--offline');
+
+    const modelFilePath = args[0];
     if (!modelFilePath) { // ensure model file path is provided
-      console.log('Usage: node generateCode.js <model-filename> <output-directory>');
+      console.log(USAGE);
       process.exit(1);
     }
     const model = fs.readFileSync(modelFilePath, 'utf8');
     const mm = new ModelManager();
+    const visitor = new Visitor();
 
     mm.addCTOModel(model, modelFilePath, true);  // true to disable consistency checks on imports
-    await mm.updateExternalModels(); // await the asynchronous call
 
-    let outputFilePath = process.argv[3];
+    // Load the dependencies from the model cache, so that no model needs to be downloaded
+    await visitor.resolveExternalModels(mm, { cacheDirectory: args[2], offline });
+
+    let outputFilePath = args[1];
     if (!outputFilePath) {  // ensure output file path is provided
-      console.log('Usage: node generateCode.js <model-filename> <output-directory>');
+      console.log(USAGE);
       process.exit(1);
     };
     if (!outputFilePath.endsWith('/')) {  // ensure output file path ends with '/'
@@ -30,9 +38,10 @@ async function main() {
     fse.emptyDirSync(outputFilePath);
 
     const parameters = {
-      fileWriter: new FileWriter(outputFilePath)
+      fileWriter: new FileWriter(outputFilePath),
+      cargo: true,
+      crateName: 'helloworld'
     };
-    const visitor = new Visitor();
 
     mm.accept(visitor, parameters);
     console.log('Code generation complete. Output files are in ' + outputFilePath + '.');
@@ -43,4 +52,6 @@ async function main() {
   }
 }
 
-main();
\ No newline at end of file
+if (require.main === module) {
+  main();
+}
@@ -1,7 +1,7 @@
 use std::fs::File;
 use std::io::Read;
 
-use helloworld::org_accordproject_helloworld::*;
+use helloworld::org_accordproject_helloworld_0_14_0::*;
 
 fn main() -> std::io::Result<()> {
     // Pull the json request object from file.
@@ -23,10 +23,7 @@ fn main() -> std::io::Result<()> {
     let output = format!("Hello Fred Blogs {}", &request.input);
 
     // Create a response object
-    let response = MyResponse {
-        class: r#"org.accordproject.helloworld.MyResponse"#.to_owned(),
-        output,
-    };
+    let response = MyResponse::new(output);
 
     // Serialise response
     let response_json = serde_json::to_string(&response).unwrap_or_else(|err| {
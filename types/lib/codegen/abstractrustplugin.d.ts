@@ -0,0 +1,70 @@
+export = AbstractRustPlugin;
+/**
+ * Plug-in class for Rust code-generation. This lists the functions that can be passed to the RustVisitor
+ * to extend the generated modules. Each function writes its Rust code with parameters.fileWriter.
+ */
+declare class AbstractRustPlugin {
+    /**
+     * Additional use declarations to generate at the top of the module of a namespace
+     * @param {ModelFile} modelFile - the model file being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addFileImports(modelFile: ModelFile, parameters: any): void;
+    /**
+     * Additional modules to generate at the end of the module of a namespace
+     * @param {ModelFile} modelFile - the model file being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addFileModules(modelFile: ModelFile, parameters: any): void;
+    /**
+     * Additional use declarations to generate in the module of a class
+     * @param {ClassDeclaration} clazz - the clazz being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addClassImports(clazz: ClassDeclaration, parameters: any): void;
+    /**
+     * Additional attributes to generate on the struct of a class
+     * @param {ClassDeclaration} clazz - the clazz being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addClassAttributes(clazz: ClassDeclaration, parameters: any): void;
+    /**
+     * Additional impl blocks to generate for the struct of a class
+     * @param {ClassDeclaration} clazz - the clazz being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addClassImpls(clazz: ClassDeclaration, parameters: any): void;
+    /**
+     * Additional modules to generate after the struct of a class
+     * @param {ClassDeclaration} clazz - the clazz being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addClassModules(clazz: ClassDeclaration, parameters: any): void;
+    /**
+     * Additional use declarations to generate in the module of an enum
+     * @param {EnumDeclaration} enumDecl - the enum being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addEnumImports(enumDecl: EnumDeclaration, parameters: any): void;
+    /**
+     * Additional attributes to generate on an enum
+     * @param {EnumDeclaration} enumDecl - the enum being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addEnumAttributes(enumDecl: EnumDeclaration, parameters: any): void;
+    /**
+     * Additional impl blocks to generate for an enum
+     * @param {EnumDeclaration} enumDecl - the enum being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addEnumImpls(enumDecl: EnumDeclaration, parameters: any): void;
+    /**
+     * Additional modules to generate after an enum
+     * @param {EnumDeclaration} enumDecl - the enum being visited
+     * @param {Object} parameters  - the parameter
+     */
+    addEnumModules(enumDecl: EnumDeclaration, parameters: any): void;
+}
+import { ModelFile } from "@accordproject/concerto-core";
+import { ClassDeclaration } from "@accordproject/concerto-core";
+import { EnumDeclaration } from "@accordproject/concerto-core";
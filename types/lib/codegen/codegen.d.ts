@@ -1,4 +1,5 @@
 import AbstractPlugin = require("./abstractplugin");
+import AbstractRustPlugin = require("./abstractrustplugin");
 import GoLangVisitor = require("./fromcto/golang/golangvisitor");
 import JSONSchemaVisitor = require("./fromcto/jsonschema/jsonschemavisitor");
 import XmlSchemaVisitor = require("./fromcto/xmlschema/xmlschemavisitor");
@@ -13,6 +14,7 @@ import MarkdownVisitor = require("./fromcto/markdown/markdownvisitor");
 import ProtobufVisitor = require("./fromcto/protobuf/protobufvisitor");
 import OpenApiVisitor = require("./fromcto/openapi/openapivisitor");
 import AvroVisitor = require("./fromcto/avro/avrovisitor");
+import RustVisitor = require("./fromcto/rust/rustvisitor");
 import InferFromJsonSchema = require("./fromJsonSchema/cto/inferModel");
 export declare namespace formats {
     export { GoLangVisitor as golang };
@@ -29,5 +31,6 @@ export declare namespace formats {
     export { ProtobufVisitor as protobuf };
     export { OpenApiVisitor as openapi };
     export { AvroVisitor as avro };
+    export { RustVisitor as rust };
 }
-export { AbstractPlugin, GoLangVisitor, JSONSchemaVisitor, XmlSchemaVisitor, PlantUMLVisitor, TypescriptVisitor, JavaVisitor, GraphQLVisitor, CSharpVisitor, ODataVisitor, MermaidVisitor, MarkdownVisitor, ProtobufVisitor, OpenApiVisitor, AvroVisitor, InferFromJsonSchema };
+export { AbstractPlugin, AbstractRustPlugin, GoLangVisitor, JSONSchemaVisitor, XmlSchemaVisitor, PlantUMLVisitor, TypescriptVisitor, JavaVisitor, GraphQLVisitor, CSharpVisitor, ODataVisitor, MermaidVisitor, MarkdownVisitor, ProtobufVisitor, OpenApiVisitor, AvroVisitor, RustVisitor, InferFromJsonSchema };
@@ -0,0 +1,24 @@
+export = ModelCache;
+/**
+ * A directory of external models, one .cto file per namespace, used to
+ * resolve the imports of a model without downloading it again.
+ * @private
+ * @class
+ * @memberof module:concerto-codegen
+ */
+declare class ModelCache {
+    constructor(cacheDirectory: string);
+    cacheDirectory: string;
+    /**
+     * Adds the models of the cache directory to a model manager, without
+     * validating them. A missing directory holds no models.
+     * @param {ModelManager} modelManager - the model manager
+     */
+    load(modelManager: any): void;
+    /**
+     * Saves the external models of a model manager to the cache directory,
+     * which is created if needed.
+     * @param {ModelManager} modelManager - the model manager
+     */
+    save(modelManager: any): void;
+}
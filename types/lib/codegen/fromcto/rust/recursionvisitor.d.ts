@@ -0,0 +1,66 @@
+export = RecursionDetectionVisitor;
+/**
+ * Detects whether ClassDeclaration contains recursive references that
+ * require indirection in Rust.
+ * Basic example:
+ * concept Person {
+ *   o Person child optional
+ * }
+ *
+ * Array fields are ignored, as a Vec already provides the indirection.
+ * Fields typed by a class with subclasses are followed into every
+ * assignable class, as they hold the union of the hierarchy.
+ *
+ * Only the cycles that close on the class at the bottom of the stack, or
+ * on one of its subclasses, which inherit its fields, are detected. Other
+ * cycles are broken by the fields that they contain.
+ *
+ * parameters.stack should be initialized to [fullyQualifiedName] of the
+ * class being checked, and parameters.roots to the fully qualified names
+ * of the classes assignable to it, which default to the class itself
+ * @private
+ * @class
+ * @memberof module:concerto-codegen
+ */
+declare class RecursionDetectionVisitor {
+    /**
+     * Visitor design pattern
+     * @param {Object} thing - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visit;
+    /**
+     * Visitor design pattern
+     * @param {ClassDeclaration} classDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitClassDeclaration;
+    /**
+     * Visitor design pattern
+     * @param {Field} field - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitField;
+    /**
+     * Visitor design pattern
+     * @param {EnumDeclaration} enumDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitEnumDeclaration;
+    /**
+     * Visitor design pattern
+     * @param {RelationshipDeclaration} relationshipDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitRelationshipDeclaration;
+}
@@ -0,0 +1,787 @@
+export = RustVisitor;
+/**
+ * Convert the contents of a ModelManager to Rust code.
+ * All generated modules are referenced from the 'lib' package
+ * with all generated modules in the same file system folder.
+ * Set the plugin property to an AbstractRustPlugin to extend the generated modules.
+ *
+ * @private
+ * @class
+ * @memberof module:concerto-codegen
+ */
+declare class RustVisitor {
+    plugin: EmptyPlugin;
+    /**
+     * Helper method: Convert any string into a valid Rust name.
+     * @param {string} input - the string to convert
+     * @returns {string} the snake case Rust name
+     */
+    toValidRustName(input: string): string;
+    /**
+     * Helper method: Converts a Concerto namespace to the names of the nested
+     * Rust modules generated for it. A namespace is a single flat module,
+     * unless nested modules are enabled, in which case each part of the
+     * namespace name is a module and the version is the innermost module,
+     * e.g. org::acme::hr::v1_0_0.
+     * @param {string} namespace - the Concerto namespace
+     * @param {Object} parameters - the parameter
+     * @return {string[]} the names of the modules, outermost first
+     * @private
+     */
+    private toRustModuleSegments;
+    /**
+     * Returns the names of the modules directly contained in a generated
+     * module, in the order of the namespaces.
+     * @param {string[]} namespaces - the Concerto namespaces
+     * @param {string[]} segments - the names of the containing module, empty for the module root
+     * @param {Object} parameters - the parameter
+     * @return {string[]} the names of the child modules
+     * @private
+     */
+    private getChildModules;
+    /**
+     * Returns true if a field must be boxed because its type contains, directly
+     * or through other types, a reference back to the class of the field, or
+     * to one of its subclasses. A field that only leads to a cycle between
+     * other classes is not boxed, as the cycle is boxed at its own fields.
+     * Array fields never need to be boxed, as a Vec already provides the
+     * indirection.
+     *
+     * Basic example:
+     * concept Person {
+     *   o Person child optional
+     * }
+     *
+     * @param {Field} field the field
+     * @returns {boolean} true if the field is recursive
+     * @private
+     */
+    private isFieldRecursive;
+    /**
+     * Visitor design pattern
+     * @param {Object} thing - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @public
+     */
+    public visit(thing: any, parameters: any): any;
+    /**
+     * Visitor design pattern
+     * @param {ModelManager} modelManager - the object being visited
+     * @param {Object} parameters - the parameter
+     * @param {Object} [parameters.primitiveTypes] - overrides of the Rust types used for Concerto primitives
+     * @param {boolean} [parameters.cargo] - write a complete Cargo crate, with a Cargo.toml and the modules under src
+     * @param {string} [parameters.crateName] - the name of the Cargo crate, defaults to concerto_model
+     * @param {string} [parameters.crateVersion] - the version of the Cargo crate, defaults to 0.1.0
+     * @param {string} [parameters.moduleRoot] - the path of the module containing the generated modules,
+     * e.g. crate::model, defaults to crate for a Cargo crate and to crate::lib otherwise
+     * @param {boolean} [parameters.nestedModules] - generate a nested module for each part of a namespace
+     * and its version, e.g. org::acme::hr::v1_0_0, instead of a single module per namespace
+     * @param {Object} [parameters.derives] - the traits to derive in addition to Debug, Serialize and
+     * Deserialize, by kind of type, e.g. { struct: ['Clone', 'PartialEq'], enum: ['Clone', 'Copy', 'PartialEq'],
+     * scalar: ['Clone', 'PartialEq'] }. The kinds are struct, enum and scalar, and unions derive the traits
+     * of structs. A struct or a union only derives the traits that the types of all of its fields implement.
+     * @param {Object} [parameters.attributes] - the attributes to write on the types of each kind,
+     * e.g. { struct: ['#[serde(deny_unknown_fields)]'] }
+     * @param {string} [parameters.timeLibrary] - the library of the DateTime type: chrono for
+     * DateTime<Utc>, the default, chrono-fixed-offset for DateTime<FixedOffset>, which keeps the
+     * offset of the values it reads, or time for OffsetDateTime in UTC
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitModelManager;
+    /**
+     * Visitor design pattern
+     * @param {ModelFile} modelFile - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitModelFile;
+    /**
+     * Returns the imports of the model files whose namespace is not in the
+     * model manager, such as external models that have not been resolved.
+     * Code is only generated when there are none; resolveExternalModels
+     * checks them to decide whether to load cached or download models.
+     * @param {ModelManager} modelManager - the model manager
+     * @return {Object[]} the importing namespace and the namespace and URI of each import
+     */
+    getUnresolvedImports(modelManager: any): any[];
+    /**
+     * Resolves the imports of the models of a model manager, so that Rust
+     * code can be generated for them. The models of the cache directory are
+     * added first; the imports that are still unresolved are then downloaded
+     * with updateExternalModels, unless offline, and the external models are
+     * saved to the cache directory.
+     * @param {ModelManager} modelManager - the model manager
+     * @param {Object} [options] - the options
+     * @param {string} [options.cacheDirectory] - the directory of the model cache, one .cto file per namespace
+     * @param {boolean} [options.offline] - never download external models
+     * @return {Promise<Object[]>} the imports that are still unresolved, as returned by getUnresolvedImports
+     */
+    resolveExternalModels(modelManager: any, options?: {
+        cacheDirectory?: string;
+        offline?: boolean;
+    }): Promise<any[]>;
+    /**
+     * Returns the types of other namespaces that are referenced by the
+     * generated module of a model file: the types of the fields of its
+     * structs and traits, or their unions. Types whose names collide with a
+     * local or another imported name get an alias prefixed by their
+     * namespace, e.g. State from org.accordproject.runtime as RuntimeState.
+     * @param {ModelFile} modelFile - the model file
+     * @return {Object[]} the namespace, name and alias of each imported type
+     * @private
+     */
+    private getImportedTypes;
+    /**
+     * Returns the name that refers to a type in the module being generated:
+     * the type itself, its import alias or, if it is not imported, its
+     * fully qualified path.
+     * @param {Property} property - the property typed by the type
+     * @param {string} name - the name of the Rust type
+     * @param {Object} parameters - the parameter
+     * @return {string} the reference to the type
+     * @private
+     */
+    private toTypeReference;
+    /**
+     * Visitor design pattern
+     * @param {ClassDeclaration} classDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitClassDeclaration;
+    /**
+     * Writes the implementation of the Identifiable trait for the struct of
+     * an identified class, returning the value of its identifier field.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeIdentifiable;
+    /**
+     * Returns the expression of the identifier value of an identified class.
+     * Identifiers typed by a String scalar are wrapped in its newtype.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {string} the Rust expression, relative to self
+     * @private
+     */
+    private toIdentifierValue;
+    /**
+     * Returns the properties of a class that are fields of its struct. The
+     * $identifier of an explicitly identified class is the value of its
+     * identifier field, so it is not a field of its own.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {Property[]} the properties
+     * @private
+     */
+    private getStructProperties;
+    /**
+     * Writes the ConcertoClass implementation of a struct, holding the fully
+     * qualified name of the Concerto type that its $class field is checked
+     * against.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeClassName;
+    /**
+     * Writes the new function of a struct, taking its required fields, and a
+     * builder that sets its optional fields and checks the Concerto
+     * validators when it builds the struct. The $class and $timestamp system
+     * properties are filled in.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeConstructors;
+    /**
+     * Returns the name of the builder setter of an optional field, which is
+     * the field name unless it would collide with the build function: the
+     * setter of a \`build\` field is then prefixed with \`with_\`, as many
+     * times as needed to differ from the other setters.
+     * @param {string} fieldName - the Rust name of the field
+     * @param {string[]} fieldNames - the Rust names of the optional fields
+     * @return {string} the setter name
+     * @private
+     */
+    private toBuilderSetterName;
+    /**
+     * Writes the Default implementation of a struct when all of its fields
+     * have a default: the modelled default if present, otherwise the default
+     * of their Rust type.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeDefault;
+    /**
+     * Returns true if the Default implementation of a struct is derived,
+     * which is the case when none of its fields has a modelled default or
+     * is a $timestamp.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} [parameters] - the parameter
+     * @return {boolean} true if the struct derives Default
+     * @private
+     */
+    private isDefaultDerivable;
+    /**
+     * Returns true if the struct of a concrete class implements Default,
+     * which requires a default for each of its fields. Relationships, unions
+     * and enums only have a default when it is modelled, and so do DateTime
+     * fields if the time library has no default DateTime.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} [parameters] - the parameter
+     * @param {string[]} [visited] - the classes being checked, to stop at recursive fields
+     * @return {boolean} true if the struct implements Default
+     * @private
+     */
+    private hasDefault;
+    /**
+     * Returns the traits derived by the struct, enum or scalar newtype of a
+     * declaration: Debug, Serialize and Deserialize, followed by the traits
+     * configured for its kind that the type of every field implements, e.g.
+     * a struct with a Double field does not derive Eq, and a struct with a
+     * String field does not derive Copy.
+     * @param {ClassDeclaration|EnumDeclaration|ScalarDeclaration} declaration - the declaration
+     * @param {Object} [parameters] - the parameter
+     * @param {string[]} [visited] - the declarations being checked, to stop at recursive fields
+     * @return {string[]} the derived traits
+     * @private
+     */
+    private getDerives;
+    /**
+     * Returns the traits derived by the union of a class: Debug and
+     * Deserialize, followed by the traits configured for structs that all
+     * of its variants derive. Serialize is implemented by the union itself
+     * and, as its variants hold data, it has no Default.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} [parameters] - the parameter
+     * @param {string[]} [visited] - the declarations being checked, to stop at recursive fields
+     * @return {string[]} the derived traits
+     * @private
+     */
+    private getUnionDerives;
+    /**
+     * Removes the derived traits whose supertraits are not derived, e.g. Copy
+     * without Clone, or Ord once Eq has been removed.
+     * @param {string[]} derives - the derived traits
+     * @return {string[]} the derived traits that can be derived together
+     * @private
+     */
+    private filterRequiredDerives;
+    /**
+     * Returns true if the Rust type of a property implements a derived trait.
+     * Vectors and boxes of recursive fields are not Copy, and neither are
+     * relationships, which implement the other standard traits only.
+     * @param {Property} property - the property
+     * @param {string} derive - the derived trait
+     * @param {Object} [parameters] - the parameter
+     * @param {string[]} [visited] - the declarations being checked, to stop at recursive fields
+     * @return {boolean} true if the trait is implemented
+     * @private
+     */
+    private isDerivable;
+    /**
+     * Returns true if a list of derived traits includes a trait, whatever its path.
+     * @param {string[]} derives - the derived traits
+     * @param {string} derive - the trait
+     * @return {boolean} true if the trait is derived
+     * @private
+     */
+    private hasDerive;
+    /**
+     * Returns true if the Rust type of a Concerto primitive implements a derived
+     * trait. Traits other than the standard ones are expected to be implemented.
+     * @param {string} type - the Concerto primitive type
+     * @param {string} derive - the derived trait
+     * @param {Object} [parameters] - the parameter
+     * @return {boolean} true if the trait is implemented
+     * @private
+     */
+    private isPrimitiveDerivable;
+    /**
+     * Returns true if the Rust type of a Concerto primitive is a floating point number.
+     * @param {string} type - the Concerto primitive type
+     * @param {Object} [parameters] - the parameter
+     * @return {boolean} true if the Rust type is f32 or f64
+     * @private
+     */
+    private isFloatType;
+    /**
+     * Returns the name of a trait without its path, e.g. Hash for std::hash::Hash.
+     * @param {string} trait - the trait
+     * @return {string} the name of the trait
+     * @private
+     */
+    private toTraitBaseName;
+    /**
+     * Writes the attributes configured for a kind of type.
+     * @param {string} kind - struct, enum or scalar
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeAttributes;
+    /**
+     * Writes the functions returning the modelled defaults of the fields
+     * declared by a class, which serde calls for missing properties.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeFieldDefaults;
+    /**
+     * Returns the Rust expression of the modelled default of a field, or null
+     * if it has none. Fields typed by a scalar use the default of the scalar.
+     * @param {Property} property - the property
+     * @param {Object} [parameters] - the parameter
+     * @return {string} the Rust expression, or null
+     * @private
+     */
+    private toDefaultValue;
+    /**
+     * Converts the default value of a Concerto primitive to a Rust expression.
+     * DateTime values without an offset are in UTC, as in Concerto, and keep
+     * their offset with the chrono-fixed-offset time library.
+     * @param {string} type - the Concerto primitive type
+     * @param {*} value - the default value
+     * @param {Object} [parameters] - the parameter
+     * @return {string} the Rust expression, or null if there is no valid default
+     * @private
+     */
+    private toRustLiteral;
+    /**
+     * Returns the concrete classes that can be assigned to a class declaration,
+     * including the class itself unless it is abstract. Enums, which extend
+     * Concept, have no $class and are left out.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {ClassDeclaration[]} the concrete class declarations
+     * @private
+     */
+    private getConcreteClassDeclarations;
+    /**
+     * Returns true if a union enum is generated for the class declaration,
+     * which is the case for all classes with a trait. The union of an abstract
+     * class without concrete subclasses has no variants. As the system types,
+     * such as Concept or Participant, are the super types of every class of
+     * the models, their unions are only generated when a property refers to them.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {boolean} true if the class has a union
+     * @private
+     */
+    private hasUnion;
+    /**
+     * Returns true if a property of a class of the model manager, a field or
+     * a relationship, is typed by a class declaration.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {boolean} true if the class is the type of a property
+     * @private
+     */
+    private isTypeReferenced;
+    /**
+     * Returns the name of the union enum generated for a class declaration.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {string} the union name
+     * @private
+     */
+    private toUnionName;
+    /**
+     * Returns the class declaration whose union types a property, or null
+     * if the property is not typed by a class with subclasses.
+     * @param {Property} property - the property
+     * @return {ClassDeclaration} the class declaration or null
+     * @private
+     */
+    private getUnionDeclaration;
+    /**
+     * Writes the union of a class hierarchy: an enum tagged by $class with
+     * a variant for each concrete class, so that a property typed by a super
+     * type is deserialized to the right subtype. The variants are serialized
+     * as is, since their $class field already holds the tag. Its ConcertoClass
+     * implementation lists the classes that a relationship to it may refer to.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeClassUnion;
+    /**
+     * Returns true if a Rust trait is generated for the class declaration,
+     * which is the case for abstract classes and classes with subclasses.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {boolean} true if the class has a trait
+     * @private
+     */
+    private hasTrait;
+    /**
+     * Returns the name of the Rust trait generated for a class declaration.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @return {string} the trait name
+     * @private
+     */
+    private toTraitName;
+    /**
+     * Returns the path of the trait of a class declaration, as referenced
+     * from the module of a namespace.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {string} namespace - the namespace of the referencing module
+     * @param {Object} parameters - the parameter
+     * @return {string} the trait path
+     * @private
+     */
+    private toTraitPath;
+    /**
+     * Writes the trait of an abstract or super type, with an accessor for each
+     * field declared by the class. The trait of the super type, if any, is a
+     * super trait, so that subtypes can be used polymorphically through it.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeClassTrait;
+    /**
+     * Writes the implementations of the traits of the class declaration
+     * and of all of its super types for the struct of the class.
+     * @param {ClassDeclaration} classDeclaration - the class declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeTraitImplementations;
+    /**
+     * Writes the validate() method of a struct, which checks the validators
+     * of all of its fields, including the inherited ones, and validates the
+     * structs and unions that its fields hold.
+     * @param {ClassDeclaration} classDeclaration - the class being visited
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeValidateMethod;
+    /**
+     * Returns true if a property holds the struct or the union of a class,
+     * rather than a primitive, a scalar, an enum or a relationship.
+     * @param {Property} property - the property
+     * @return {boolean} true if the property holds a class
+     * @private
+     */
+    private isClassField;
+    /**
+     * Writes the validate() method of a concrete class, followed by the
+     * validation and deserialization functions for the validated fields
+     * declared by the class itself. Inherited fields reuse the functions
+     * written for their declaring class.
+     * @param {ClassDeclaration} classDeclaration - the class being visited
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeClassValidation;
+    /**
+     * Visitor design pattern
+     * @param {ScalarDeclaration} scalarDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitScalarDeclaration;
+    /**
+     * Writes the validating constructor, accessors and Deserialize
+     * implementation of a scalar newtype.
+     * @param {ScalarDeclaration} scalarDeclaration - the scalar being visited
+     * @param {Object} validator - the validator constraints of the scalar
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeScalarValidation;
+    /**
+     * Writes the calls to the utils validation functions for a validator.
+     * @param {string} path - the name reported in validation errors
+     * @param {string} type - the Concerto primitive type being validated
+     * @param {Object} validator - the validator constraints
+     * @param {string} value - the Rust expression referencing the value
+     * @param {number} indent - the indentation level
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeValidatorChecks;
+    /**
+     * Returns the validator constraints of a field, or null if the field is
+     * not constrained. Fields typed by a scalar are validated by the scalar.
+     * @param {Property} property - the property
+     * @return {Object} the validator constraints or null
+     * @private
+     */
+    private getFieldValidator;
+    /**
+     * Returns the validator constraints of the scalar typing a field, or
+     * null if the field is not typed by a validated scalar.
+     * @param {Property} property - the property
+     * @return {Object} the validator constraints or null
+     * @private
+     */
+    private getScalarValidator;
+    /**
+     * Extracts the constraints of a Concerto validator: a regex and length
+     * bounds for Strings, or a range for numeric types.
+     * @param {string} type - the Concerto primitive type being validated
+     * @param {Object} validator - the Concerto validator
+     * @return {Object} the validator constraints or null
+     * @private
+     */
+    private getValidator;
+    /**
+     * Visitor design pattern
+     * @param {Field} field - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitField;
+    /**
+     * Visitor design pattern
+     * @param {EnumDeclaration} enumDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitEnumDeclaration;
+    /**
+     * Visitor design pattern
+     * @param {EnumValueDeclaration} enumValueDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitEnumValueDeclaration;
+    /**
+     * Writes the ALL constant and the as_str method of an enum, and its
+     * Display and FromStr implementations, which use the Concerto names of
+     * the values.
+     * @param {EnumDeclaration} enumDeclaration - the enum declaration
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private writeEnumImpls;
+    /**
+     * Returns the Rust variants of the values of an enum, by the names of the
+     * values. A variant that collides with Self or with a previous variant,
+     * e.g. Active for both ACTIVE and Active, gets an underscore suffix.
+     * @param {EnumDeclaration} enumDeclaration - the enum declaration
+     * @return {Map<string, string>} the variants by the names of the values
+     * @private
+     */
+    private getEnumVariants;
+    /**
+     * Converts the name of an enum value to an UpperCamelCase Rust variant,
+     * e.g. PassedTesting for PASSED_TESTING or passedTesting. Words in
+     * capitals are lowercased, and a name that does not start with a letter
+     * gets a V prefix.
+     * @param {string} name - the name of the enum value
+     * @return {string} the name of the variant
+     * @private
+     */
+    private toRustVariantName;
+    /**
+     * Visitor design pattern
+     * @param {RelationshipDeclaration} relationshipDeclaration - the object being visited
+     * @param {Object} parameters - the parameter
+     * @return {Object} the result of visiting or null
+     * @private
+     */
+    private visitRelationshipDeclaration;
+    /**
+     * Converts a Concerto type to a Rust type. Primitive types use the
+     * defaults below unless they are overridden by parameters.primitiveTypes,
+     * e.g. { Long: 'i128' }. Other types keep their Concerto name.
+     * @param {string} type - the Concerto type
+     * @param {Object} [parameters] - the parameter
+     * @param {Object} [parameters.primitiveTypes] - overrides of the Rust types used for Concerto primitives
+     * @return {string} the Rust type
+     * @private
+     */
+    private toRustType;
+    /**
+     * Returns the time library of the DateTime type, chrono unless
+     * parameters.timeLibrary selects another one.
+     * @param {Object} [parameters] - the parameter
+     * @param {string} [parameters.timeLibrary] - the name of the time library
+     * @return {Object} the time library
+     * @private
+     */
+    private getTimeLibrary;
+    /**
+     * Returns true if the Concerto type is a DateTime.
+     * @param {string} type - the Concerto type
+     * @return {boolean} true if the type is a DateTime
+     * @private
+     */
+    private isDateField;
+    /**
+     * Returns the path of the Rust module that contains the generated modules.
+     * Unless configured, the modules of a Cargo crate are at its root and
+     * other modules are expected in a lib module.
+     * @param {Object} parameters - the parameter
+     * @return {string} the module path
+     * @private
+     */
+    private toModuleRoot;
+    /**
+     * Returns the path of the Rust module generated for a namespace.
+     * @param {string} namespace - the Concerto namespace
+     * @param {Object} parameters - the parameter
+     * @return {string} the module path
+     * @private
+     */
+    private toRustModulePath;
+    /**
+     * Returns the path of a generated Rust source file, which is in the src
+     * folder of a Cargo crate.
+     * @param {string} fileName - the name of the file
+     * @param {Object} parameters - the parameter
+     * @return {string} the path of the file
+     * @private
+     */
+    private toSourceFilePath;
+    /**
+     * Writes the Cargo.toml of a generated crate, with the dependencies
+     * used by the generated code.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private addCargoManifest;
+    /**
+     * Converts a string to an UpperCamelCase Rust identifier.
+     * @param {string} input - the string
+     * @return {string} the identifier
+     * @private
+     */
+    private toUpperCamelCase;
+    /**
+     * Returns the name of the struct field generated for a Concerto property.
+     * @param {string} name - the Concerto property name
+     * @return {string} the Rust field name
+     * @private
+     */
+    private toRustFieldName;
+    /**
+     * Returns the Rust type of a field, including any Vec or Option wrapper.
+     * @param {Field} field - the field
+     * @param {Object} [parameters] - the parameter
+     * @return {string} the Rust type
+     * @private
+     */
+    private toFieldRustType;
+    /**
+     * Returns the type used to borrow the value of a field in a function argument.
+     * @param {Field} field - the field
+     * @param {Object} [parameters] - the parameter
+     * @return {string} the Rust reference type
+     * @private
+     */
+    private toFieldReferenceType;
+    /**
+     * Returns the name of a generated function dedicated to a field,
+     * e.g. validate_person_first_name.
+     * @param {string} prefix - the function prefix
+     * @param {Field} field - the field
+     * @return {string} the function name
+     * @private
+     */
+    private toFieldFunctionName;
+    /**
+     * Returns the path of a generated function dedicated to a field, which
+     * is qualified when the field is inherited from another namespace.
+     * @param {string} prefix - the function prefix
+     * @param {Field} field - the field
+     * @param {Object} parameters - the parameter
+     * @return {string} the function path
+     * @private
+     */
+    private toFieldFunctionPath;
+    /**
+     * Converts a JavaScript regular expression to a Rust regex pattern,
+     * translating the supported flags to inline flags. The regex crate has
+     * no look-around assertions or backreferences, so patterns using them
+     * are rejected rather than generating a validator that never matches.
+     * @param {RegExp} regex - the regular expression
+     * @param {string} path - the scalar or field validated by the regex
+     * @return {string} the Rust regex pattern
+     * @private
+     */
+    private toRustRegex;
+    /**
+     * Converts a string to a Rust raw string literal.
+     * @param {string} value - the string
+     * @return {string} the Rust raw string literal
+     * @private
+     */
+    private toRustRawString;
+    /**
+     * Converts a number to a Rust literal of the given numeric type.
+     * @param {number} value - the number
+     * @param {string} rustType - the Rust numeric type
+     * @return {string} the Rust literal
+     * @private
+     */
+    private toRustNumber;
+    /**
+     * Writes the utils file shared by the generated modules.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private addUtilsModelFile;
+    /**
+     * Adds the serde helpers of the DateTime fields to the utils file. There
+     * is a pair of functions for required, optional, array and optional array
+     * fields, and optional fields serialize None as null and deserialize null
+     * as None.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private addDateTimeUtils;
+    /**
+     * Adds the functions that parse and format a Concerto DateTime with the
+     * time library to the utils file.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private addDateTimeFunctions;
+    /**
+     * Adds the ValidationError type and the functions that check the Concerto
+     * regex, length and range validators to the utils file.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private addValidationUtils;
+    /**
+     * Adds the ConcertoClass trait and the Class type of the $class field to
+     * the utils file. A Class always serializes to the fully qualified name
+     * of its type, and fails to deserialize from any other name.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private addClassUtils;
+    /**
+     * Adds the Identifiable trait, implemented by identified assets,
+     * participants and other identified classes, to the utils file.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private addIdentifiableUtils;
+    /**
+     * Adds the EnumError type, returned when an enum is parsed from a string
+     * that is not one of its values, to the utils file.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private addEnumUtils;
+    /**
+     * Adds the generic Relationship type to the utils file. A relationship
+     * holds the resource URI of its target rather than embedding it, and keeps
+     * the target type as a marker for compile-time safety. A URI is only
+     * parsed if it refers to one of the classes of the target type.
+     * @param {Object} parameters - the parameter
+     * @private
+     */
+    private addRelationshipUtils;
+}
+import EmptyPlugin = require("./emptyplugin");